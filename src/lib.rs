@@ -13,20 +13,41 @@ mod balances_prover {
 
     use super::pink;
     use crate::{
-        state_proofs::{rpc::Rpc, verifier},
+        state_proofs::{
+            chain::Chain,
+            rpc::Rpc,
+            source::{ProofSource, SuppliedProof},
+        },
         types::{
             access_control::{AccessControl, SudoAccount},
-            balances::{Asset, BalanceProverMessage, ProverRequest},
-            crypto::ecdsa::{ContractKeyPair, ContractSeed},
-            evm::Address,
-            Error, ProverStatus, Result, SnapshotCommitment, SubstrateStateProof,
+            balances::{
+                balance_commitment, request_nonce, Asset, AssetEntry, AssetId, AssetKey,
+                BalanceCommitmentMessage, BalanceKind, BalanceProverMessage, BalanceValueLayout,
+                ClaimConsentPayload, CommitmentRequest, DelegatedProofPayload, EvmTarget,
+                ProverRequest, ReplayProtection, RequestType, StorageKeyLayout, ThresholdRequest,
+            },
+            crypto::{
+                ecdsa::{ContractKeyPair, ContractSeed},
+                multi_signature::{verify_account_signature, SignerType},
+            },
+            eip712::Eip712Struct,
+            evm::{eth_signed_message_hash, Address, RecoveryIdFormat, SignatureScheme},
+            grandpa::AuthoritySet,
+            header::Header,
+            ChainId, Error, ProverStatus, ProverStatusInfo, Result, SnapshotBlock,
         },
-        utils::balances::{BalanceProverMessageBuilder, BalanceStorageKeyBuilder, StorageItemKey},
+        utils::balances::{BalanceProverMessageBuilder, BalanceStorageKeyBuilder},
     };
     use alloc::{string::String, vec::Vec};
-    use ink::storage::Lazy;
+    use ink::storage::{Lazy, Mapping};
     use pink::PinkEnvironment;
-    use scale::Decode;
+    use scale::Encode;
+
+    /// The default validity (in seconds) of a signed request
+    const DEFAULT_SIGNATURE_TTL: u64 = 60 * 60;
+
+    /// The length of the salt of a balance commitment
+    const COMMITMENT_SALT_LENGTH: u8 = 32;
 
     /// Defines the storage of your contract.
     /// All the fields will be encrypted and stored on-chain.
@@ -39,48 +60,104 @@ mod balances_prover {
         evm_address: Address,
         /// The seed of the contract for the EVM address
         seed: Lazy<ContractSeed>,
-        /// The chain snapshot commitment
-        snapshot: SnapshotCommitment,
-        /// The balances storage key prefix,
-        storage_key_prefix: Vec<u8>,
-        /// The asset for which the balance needs to be checked
-        asset: Asset,
-        /// The RPC that handles the read requests of state proofs
-        rpc: Rpc,
+        /// The registry of the chains whose balances can be checked
+        chains: Mapping<ChainId, Chain>,
+        /// The ids of the registered chains
+        chain_ids: Vec<ChainId>,
+        /// The registry of the assets for which the balance can be checked
+        assets: Mapping<AssetKey, AssetEntry>,
+        /// The keys of the registered assets
+        asset_keys: Vec<AssetKey>,
+        /// The asset for which the balance is checked by the single asset messages.
+        /// Its chain is the one targeted by the single chain messages.
+        default_asset: AssetKey,
         /// The status of the prover contract
-        status: ProverStatus,
+        status: ProverStatusInfo,
+        /// The EVM chain and contract that verify the signed requests
+        evm_target: EvmTarget,
+        /// The validity (in seconds) of a signed request
+        signature_ttl: u64,
+        /// The scheme used to hash requests before signing them
+        signature_scheme: SignatureScheme,
+        /// The format of the recovery id within the signatures
+        recovery_id_format: RecoveryIdFormat,
+        /// Whether the claim address must consent to the claim with a signature
+        claim_consent_required: bool,
+        /// The balance thresholds of the tiers of each asset
+        balance_tiers: Mapping<AssetKey, Vec<Balance>>,
     }
 
     impl BalancesProver {
         /// Constructor to initializes your contract
         /// `state_root` is the state root of the block of which you want to take the snapshot for balances
         ///
+        /// The chain is registered as `chain_id` and `asset` is registered as the default asset of it.
+        /// The snapshot is taken at the header of the snapshot block, once it is checked to hash to the block hash,
+        /// and parachain snapshots through the supplied relay chain block. Its finality is not verified,
+        /// hence the snapshot is trusted from the deployer.
         #[ink(constructor)]
+        #[allow(clippy::too_many_arguments)]
         pub fn new(
-            snapshot: SnapshotCommitment,
-            storage_key_prefix: Vec<u8>,
+            chain_id: ChainId,
+            genesis_hash: Vec<u8>,
+            snapshot: SnapshotBlock,
+            storage_key: StorageKeyLayout,
+            value_layout: BalanceValueLayout,
+            kind: BalanceKind,
             asset: Asset,
             http_endpoint: String,
+            relay_http_endpoint: Option<String>,
             status: ProverStatus,
-        ) -> Self {
+            evm_target: EvmTarget,
+        ) -> Result<Self> {
             let sudo = pink::env().caller();
+            asset.validate()?;
 
-            let pair = ContractKeyPair::generate(&snapshot.block_hash);
+            let chain = Chain::new(
+                genesis_hash,
+                snapshot,
+                Rpc::new(http_endpoint),
+                relay_http_endpoint.map(Rpc::new),
+            )?;
+
+            let pair = ContractKeyPair::generate(&chain.snapshot.block_hash);
             let public = pair.public();
 
             let mut seed = Lazy::new();
             seed.set(&pair.into());
 
-            Self {
+            let mut chains = Mapping::default();
+            chains.insert(chain_id, &chain);
+
+            let entry = AssetEntry {
+                chain_id,
+                asset,
+                storage_key,
+                value_layout,
+                kind,
+                enabled: true,
+            };
+            let default_asset = entry.key();
+            let mut assets = Mapping::default();
+            assets.insert(default_asset, &entry);
+
+            Ok(Self {
                 sudo,
                 evm_address: public.into(),
                 seed,
-                snapshot,
-                storage_key_prefix,
-                asset,
-                rpc: Rpc::new(http_endpoint),
-                status,
-            }
+                chains,
+                chain_ids: Vec::from([chain_id]),
+                assets,
+                asset_keys: Vec::from([default_asset]),
+                default_asset,
+                status: ProverStatusInfo::new(status, sudo, pink::env().block_timestamp()),
+                evm_target,
+                signature_ttl: DEFAULT_SIGNATURE_TTL,
+                signature_scheme: SignatureScheme::Keccak256,
+                recovery_id_format: RecoveryIdFormat::Raw,
+                claim_consent_required: false,
+                balance_tiers: Mapping::default(),
+            })
         }
 
         // Ensures the caller account has sudo permissions
@@ -109,6 +186,213 @@ mod balances_prover {
             self.evm_address = address;
         }
 
+        // Obtains the registered chain
+        fn registered_chain(&self, chain_id: ChainId) -> Result<Chain> {
+            self.chains.get(chain_id).ok_or(Error::ChainNotFound)
+        }
+
+        // The chain of the default asset, which is targeted by the single chain messages
+        fn default_chain_id(&self) -> ChainId {
+            self.default_asset.0
+        }
+
+        // Obtains the chain of the default asset, which is always registered
+        fn default_chain(&self) -> Chain {
+            self.chains
+                .get(self.default_chain_id())
+                .expect("The chain of the default asset cannot be removed")
+        }
+
+        // Obtains the registered asset, if it is enabled
+        fn enabled_asset(&self, key: AssetKey) -> Result<AssetEntry> {
+            let entry = self.assets.get(key).ok_or(Error::AssetNotFound)?;
+            if !entry.enabled {
+                return Err(Error::AssetDisabled);
+            }
+
+            Ok(entry)
+        }
+
+        // Obtains the default asset, which is always registered
+        fn default_asset_entry(&self) -> AssetEntry {
+            self.assets
+                .get(self.default_asset)
+                .expect("The default asset cannot be removed")
+        }
+
+        // Reads the balance of `who` for the asset from a state proof of the source verified against the chain snapshot
+        fn verified_balance(
+            &self,
+            who: AccountId,
+            entry: &AssetEntry,
+            chain: &Chain,
+            source: &dyn ProofSource,
+        ) -> Result<Balance> {
+            // Construct the storage key to retrieve the account balance amount
+            let storage_key =
+                BalanceStorageKeyBuilder::from_layout(&entry.storage_key, entry.asset.id(), who);
+
+            // Verify the state proof and read the value
+            let value = chain
+                .read_value(&storage_key, source)?
+                .ok_or(Error::InvalidBalance)?;
+            entry.value_layout.decode_balance(&value, entry.kind)
+        }
+
+        // The replay protection of the request of `who` for the asset at the snapshot block.
+        // Nothing is stored, since requests are signed within queries: the EVM verifier consumes the nonces.
+        fn replay_protection(
+            &self,
+            request_type: RequestType,
+            who: AccountId,
+            key: AssetKey,
+            snapshot: &[u8],
+        ) -> ReplayProtection {
+            // Block timestamps are in milliseconds while the EVM uses seconds
+            let now = self.env().block_timestamp() / 1000;
+
+            ReplayProtection::new(
+                request_type,
+                snapshot,
+                &who,
+                key,
+                now.saturating_add(self.signature_ttl),
+                self.evm_target,
+            )
+        }
+
+        // Ensures the claim address is not required to consent to the claim
+        fn ensure_claim_consent_not_required(&self) -> Result<()> {
+            if self.claim_consent_required {
+                return Err(Error::ClaimConsentRequired);
+            }
+
+            Ok(())
+        }
+
+        // Reads the balance of `who` for the enabled asset, along with the asset and its chain
+        fn verified_asset_balance(
+            &self,
+            who: AccountId,
+            key: AssetKey,
+            supplied_proof: Option<SuppliedProof>,
+        ) -> Result<(AssetEntry, Chain, Balance)> {
+            let entry = self.enabled_asset(key)?;
+            let chain = self.registered_chain(entry.chain_id)?;
+            // A proof supplied by the caller skips the RPC
+            let source: &dyn ProofSource = match &supplied_proof {
+                Some(proof) => proof,
+                None => &chain.rpc,
+            };
+            let amount = self.verified_balance(who, &entry, &chain, source)?;
+
+            Ok((entry, chain, amount))
+        }
+
+        // Signs the request with the contract key and the configured signature scheme
+        fn sign_request<R: Encode + Eip712Struct>(&self, request: R) -> BalanceProverMessage {
+            BalanceProverMessageBuilder::default()
+                .request(request)
+                .sign_request(
+                    &self.pair(),
+                    &self.signature_scheme,
+                    self.recovery_id_format,
+                    &self.evm_target,
+                )
+                .build()
+        }
+
+        // Signs the balance of `who` for the asset, assigned to `claim_address`
+        fn sign_balance(
+            &self,
+            who: AccountId,
+            key: AssetKey,
+            claim_address: Address,
+            supplied_proof: Option<SuppliedProof>,
+        ) -> Result<BalanceProverMessage> {
+            let (entry, chain, amount) = self.verified_asset_balance(who, key, supplied_proof)?;
+            let amount = entry.asset.bucketing().apply(amount);
+            let replay_protection =
+                self.replay_protection(RequestType::Balance, who, key, &chain.snapshot.block_hash);
+
+            // Return the prover message
+            let request = ProverRequest::new(
+                chain.genesis_hash,
+                who,
+                claim_address,
+                entry.asset,
+                amount,
+                entry.kind,
+                replay_protection,
+            );
+
+            Ok(self.sign_request(request))
+        }
+
+        // Signs a salted commitment to the balance of `who` for the asset, assigned to `claim_address`
+        fn sign_commitment(
+            &self,
+            who: AccountId,
+            key: AssetKey,
+            claim_address: Address,
+        ) -> Result<BalanceCommitmentMessage> {
+            let (entry, chain, amount) = self.verified_asset_balance(who, key, None)?;
+            let replay_protection = self.replay_protection(
+                RequestType::Commitment,
+                who,
+                key,
+                &chain.snapshot.block_hash,
+            );
+
+            let salt = pink::ext().getrandom(COMMITMENT_SALT_LENGTH);
+            let commitment = balance_commitment(&who, &entry.asset, amount, &salt);
+
+            let request = CommitmentRequest::new(
+                chain.genesis_hash,
+                who,
+                claim_address,
+                entry.asset,
+                entry.kind,
+                commitment,
+                replay_protection,
+            );
+
+            Ok(BalanceCommitmentMessage {
+                message: self.sign_request(request),
+                amount,
+                salt,
+            })
+        }
+
+        // Signs that the balance of `who` for the asset meets `threshold`, assigned to `claim_address`
+        fn sign_threshold(
+            &self,
+            who: AccountId,
+            claim_address: Address,
+            entry: AssetEntry,
+            chain: Chain,
+            threshold: Balance,
+        ) -> Result<BalanceProverMessage> {
+            let replay_protection = self.replay_protection(
+                RequestType::Threshold,
+                who,
+                entry.key(),
+                &chain.snapshot.block_hash,
+            );
+
+            let request = ThresholdRequest::new(
+                chain.genesis_hash,
+                who,
+                claim_address,
+                entry.asset,
+                entry.kind,
+                threshold,
+                replay_protection,
+            );
+
+            Ok(self.sign_request(request))
+        }
+
         /// The EVM address of the contract used to sign messages
         #[ink(message)]
         pub fn address(&self) -> Address {
@@ -121,10 +405,156 @@ mod balances_prover {
             self.sudo
         }
 
-        /// The rpc url
+        /// The rpc url of the default chain
         #[ink(message)]
         pub fn rpc_url(&self) -> String {
-            self.rpc.url.clone()
+            self.default_chain().rpc.url()
+        }
+
+        /// The relay chain rpc url of the default chain
+        #[ink(message)]
+        pub fn relay_rpc_url(&self) -> Option<String> {
+            self.default_chain().relay_rpc.map(|rpc| rpc.url())
+        }
+
+        /// The rpc endpoints of the chain, in the order they are requested
+        #[ink(message)]
+        pub fn rpc_endpoints(&self, chain_id: ChainId) -> Result<Vec<String>> {
+            Ok(self.registered_chain(chain_id)?.rpc.endpoints())
+        }
+
+        /// The number of rpc endpoints of the chain that must return the same result
+        #[ink(message)]
+        pub fn rpc_quorum(&self, chain_id: ChainId) -> Result<u32> {
+            Ok(self.registered_chain(chain_id)?.rpc.quorum())
+        }
+
+        /// The relay chain rpc endpoints of the chain, in the order they are requested
+        #[ink(message)]
+        pub fn relay_rpc_endpoints(&self, chain_id: ChainId) -> Result<Option<Vec<String>>> {
+            Ok(self
+                .registered_chain(chain_id)?
+                .relay_rpc
+                .map(|rpc| rpc.endpoints()))
+        }
+
+        /// The number of relay chain rpc endpoints of the chain that must return the same result
+        #[ink(message)]
+        pub fn relay_rpc_quorum(&self, chain_id: ChainId) -> Result<Option<u32>> {
+            Ok(self
+                .registered_chain(chain_id)?
+                .relay_rpc
+                .map(|rpc| rpc.quorum()))
+        }
+
+        /// The prover status, along with who last changed it and when
+        #[ink(message)]
+        pub fn prover_status(&self) -> ProverStatusInfo {
+            self.status.clone()
+        }
+
+        /// The EVM chain and contract that verify the signed requests
+        #[ink(message)]
+        pub fn evm_target(&self) -> EvmTarget {
+            self.evm_target
+        }
+
+        /// The scheme used to hash requests before signing them
+        #[ink(message)]
+        pub fn signature_scheme(&self) -> SignatureScheme {
+            self.signature_scheme.clone()
+        }
+
+        /// The format of the recovery id within the signatures
+        #[ink(message)]
+        pub fn recovery_id_format(&self) -> RecoveryIdFormat {
+            self.recovery_id_format
+        }
+
+        /// Whether the claim address must consent to the claim with a signature
+        #[ink(message)]
+        pub fn claim_consent_required(&self) -> bool {
+            self.claim_consent_required
+        }
+
+        /// The trusted GRANDPA authority set of the default chain
+        #[ink(message)]
+        pub fn authority_set(&self) -> Option<AuthoritySet> {
+            self.default_chain().authority_set
+        }
+
+        /// The ids of the registered chains
+        #[ink(message)]
+        pub fn chains(&self) -> Vec<ChainId> {
+            self.chain_ids.clone()
+        }
+
+        /// The registered chain with the given id
+        #[ink(message)]
+        pub fn chain(&self, chain_id: ChainId) -> Option<Chain> {
+            self.chains.get(chain_id)
+        }
+
+        /// The keys of the registered assets
+        #[ink(message)]
+        pub fn assets(&self) -> Vec<AssetKey> {
+            self.asset_keys.clone()
+        }
+
+        /// The registered asset with the given id on the chain
+        #[ink(message)]
+        pub fn asset(&self, chain_id: ChainId, asset_id: AssetId) -> Option<AssetEntry> {
+            self.assets.get((chain_id, asset_id))
+        }
+
+        /// The asset for which the balance is checked by the single asset messages
+        #[ink(message)]
+        pub fn default_asset(&self) -> AssetKey {
+            self.default_asset
+        }
+
+        /// The balance thresholds of the tiers of the asset
+        #[ink(message)]
+        pub fn balance_tiers(&self, chain_id: ChainId, asset_id: AssetId) -> Vec<Balance> {
+            self.balance_tiers
+                .get((chain_id, asset_id))
+                .unwrap_or_default()
+        }
+
+        /// The nonce of the signed requests of the type of `account` for the asset at the snapshot of its chain,
+        /// which the EVM verifier consumes when it accepts one of them
+        #[ink(message)]
+        pub fn nonce_of(
+            &self,
+            account: AccountId,
+            chain_id: ChainId,
+            asset_id: AssetId,
+            request_type: RequestType,
+        ) -> Result<u64> {
+            let chain = self.registered_chain(chain_id)?;
+
+            Ok(request_nonce(
+                request_type,
+                &chain.snapshot.block_hash,
+                &account,
+                (chain_id, asset_id),
+            ))
+        }
+
+        /// The block of the chain at `height` along with the evidence to submit it to the snapshot messages.
+        ///
+        /// For parachains, `height` is the height of the relay chain block whose parachain head is the snapshot block.
+        #[ink(message)]
+        pub fn snapshot_block(&self, chain_id: ChainId, height: u32) -> Result<SnapshotBlock> {
+            self.registered_chain(chain_id)?.snapshot_block(height)
+        }
+
+        /// The SCALE encoded `FinalityProof` of the block of the chain at `height`, if it is finalized.
+        ///
+        /// For parachains, `height` is the height of the relay chain block.
+        #[ink(message)]
+        pub fn finality_proof(&self, chain_id: ChainId, height: u32) -> Result<Option<Vec<u8>>> {
+            self.registered_chain(chain_id)?.finality_proof(height)
         }
 
         /// Derives a new contract seed and changes the associated EVM address
@@ -142,82 +572,966 @@ mod balances_prover {
             Ok(())
         }
 
-        /// Updates the snapshot
+        /// Registers a new chain identified by its genesis hash at the snapshot block,
+        /// whose header is checked to hash to the block hash but whose finality is not verified
+        #[ink(message)]
+        pub fn force_add_chain(
+            &mut self,
+            chain_id: ChainId,
+            genesis_hash: Vec<u8>,
+            snapshot: SnapshotBlock,
+            http_endpoint: String,
+            relay_http_endpoint: Option<String>,
+        ) -> Result<()> {
+            self.ensure_root()?;
+
+            if self.chains.contains(chain_id) {
+                return Err(Error::ChainAlreadyRegistered);
+            }
+
+            let chain = Chain::new(
+                genesis_hash,
+                snapshot,
+                Rpc::new(http_endpoint),
+                relay_http_endpoint.map(Rpc::new),
+            )?;
+
+            self.chains.insert(chain_id, &chain);
+            self.chain_ids.push(chain_id);
+            Ok(())
+        }
+
+        /// Removes a registered chain, once all of its assets are removed
+        #[ink(message)]
+        pub fn force_remove_chain(&mut self, chain_id: ChainId) -> Result<()> {
+            self.ensure_root()?;
+
+            if !self.chains.contains(chain_id) {
+                return Err(Error::ChainNotFound);
+            }
+            if self.asset_keys.iter().any(|(id, _)| *id == chain_id) {
+                return Err(Error::ChainInUse);
+            }
+
+            self.chains.remove(chain_id);
+            self.chain_ids.retain(|id| *id != chain_id);
+            Ok(())
+        }
+
+        /// Updates the snapshot of the default chain to the snapshot block
+        #[ink(message)]
+        pub fn force_update_snapshot(
+            &mut self,
+            snapshot: SnapshotBlock,
+            finality_proof: Option<Vec<u8>>,
+        ) -> Result<()> {
+            self.force_update_chain_snapshot(self.default_chain_id(), snapshot, finality_proof)
+        }
+
+        /// Updates the snapshot of the chain to the snapshot block, which may be older or of another parachain.
+        ///
+        /// Once the chain has a trusted authority set, `finality_proof` is required and the snapshot must be finalized
+        /// by the set, otherwise the snapshot is trusted from sudo.
+        #[ink(message)]
+        pub fn force_update_chain_snapshot(
+            &mut self,
+            chain_id: ChainId,
+            snapshot: SnapshotBlock,
+            finality_proof: Option<Vec<u8>>,
+        ) -> Result<()> {
+            self.ensure_root()?;
+
+            let mut chain = self.registered_chain(chain_id)?;
+            chain.force_update_snapshot(snapshot, finality_proof.as_deref())?;
+            self.chains.insert(chain_id, &chain);
+            Ok(())
+        }
+
+        /// Updates the snapshot of the default chain with a newer one that has been finalized by the trusted authority set.
+        ///
+        /// Anyone can update the snapshot, since its finality is proven.
+        #[ink(message)]
+        pub fn update_snapshot(
+            &mut self,
+            snapshot: SnapshotBlock,
+            finality_proof: Vec<u8>,
+        ) -> Result<()> {
+            self.update_chain_snapshot(self.default_chain_id(), snapshot, finality_proof)
+        }
+
+        /// Updates the snapshot of the chain with a newer one that has been finalized by the trusted authority set.
+        ///
+        /// Anyone can update the snapshot, since its finality is proven. `finality_proof` is the SCALE encoded
+        /// `FinalityProof` of the snapshot block, or of its relay chain block for parachain snapshots.
+        /// The snapshot must be of the same parachain as the stored one, or of the relay chain itself,
+        /// and keeps the state versions of the stored one, which only sudo can change.
+        #[ink(message)]
+        pub fn update_chain_snapshot(
+            &mut self,
+            chain_id: ChainId,
+            snapshot: SnapshotBlock,
+            finality_proof: Vec<u8>,
+        ) -> Result<()> {
+            let mut chain = self.registered_chain(chain_id)?;
+            chain.update_snapshot(snapshot, &finality_proof)?;
+            self.chains.insert(chain_id, &chain);
+            Ok(())
+        }
+
+        /// Requires or not the claim address to consent to the claim with a signature
+        #[ink(message)]
+        pub fn force_update_claim_consent_required(&mut self, required: bool) -> Result<()> {
+            self.ensure_root()?;
+
+            self.claim_consent_required = required;
+            Ok(())
+        }
+
+        /// Updates the trusted GRANDPA authority set of the default chain
+        #[ink(message)]
+        pub fn force_update_authority_set(&mut self, set: AuthoritySet) -> Result<()> {
+            self.force_update_chain_authority_set(self.default_chain_id(), set)
+        }
+
+        /// Updates the trusted GRANDPA authority set of the chain.
+        ///
+        /// `enacted_at` is the number of the block that enacted the set, authority set changes scheduled up to it are rejected.
+        #[ink(message)]
+        pub fn force_update_chain_authority_set(
+            &mut self,
+            chain_id: ChainId,
+            set: AuthoritySet,
+        ) -> Result<()> {
+            self.ensure_root()?;
+            set.validate()?;
+
+            let mut chain = self.registered_chain(chain_id)?;
+            chain.authority_set = Some(set);
+            self.chains.insert(chain_id, &chain);
+            Ok(())
+        }
+
+        /// Advances the trusted GRANDPA authority set of the default chain to the one scheduled by a header,
+        /// once the change is enacted by a finalized block.
+        ///
+        /// `header` is the SCALE encoded header and `finality_proof` the SCALE encoded `FinalityProof`
+        /// returned by `grandpa_proveFinality` for it.
+        #[ink(message)]
+        pub fn advance_authority_set(
+            &mut self,
+            header: Vec<u8>,
+            finality_proof: Vec<u8>,
+        ) -> Result<()> {
+            self.advance_chain_authority_set(self.default_chain_id(), header, finality_proof)
+        }
+
+        /// Advances the trusted GRANDPA authority set of the chain to the one scheduled by a header,
+        /// once the change is enacted by a finalized block.
+        ///
+        /// `header` is the SCALE encoded header and `finality_proof` the SCALE encoded `FinalityProof`
+        /// returned by `grandpa_proveFinality` for it. Forced changes must be applied by sudo.
+        #[ink(message)]
+        pub fn advance_chain_authority_set(
+            &mut self,
+            chain_id: ChainId,
+            header: Vec<u8>,
+            finality_proof: Vec<u8>,
+        ) -> Result<()> {
+            let mut chain = self.registered_chain(chain_id)?;
+
+            chain.advance_authority_set(&Header::new(header), &finality_proof)?;
+            self.chains.insert(chain_id, &chain);
+            Ok(())
+        }
+
+        /// Registers a new asset of a registered chain
+        #[ink(message)]
+        pub fn force_add_asset(&mut self, entry: AssetEntry) -> Result<()> {
+            self.ensure_root()?;
+
+            entry.asset.validate()?;
+            let key = entry.key();
+            if !self.chains.contains(entry.chain_id) {
+                return Err(Error::ChainNotFound);
+            }
+            if self.assets.contains(key) {
+                return Err(Error::AssetAlreadyRegistered);
+            }
+
+            self.assets.insert(key, &entry);
+            self.asset_keys.push(key);
+            Ok(())
+        }
+
+        /// Registers a new asset whose balances are stored in a `pallet_assets` accounts double map,
+        /// e.g. `pallet` "Assets" and `item` "Account"
+        #[ink(message)]
+        pub fn force_register_pallet_asset(
+            &mut self,
+            chain_id: ChainId,
+            asset: Asset,
+            pallet: String,
+            item: String,
+        ) -> Result<()> {
+            self.force_add_asset(AssetEntry {
+                chain_id,
+                asset,
+                storage_key: StorageKeyLayout::pallet_assets(pallet, item),
+                value_layout: BalanceValueLayout::AssetAccount,
+                kind: BalanceKind::Free,
+                enabled: true,
+            })
+        }
+
+        /// Registers a new asset whose balances are stored in `orml_tokens::Accounts`,
+        /// where `currency_id` is the SCALE encoded `CurrencyId` of the asset
+        #[ink(message)]
+        pub fn force_register_orml_asset(
+            &mut self,
+            chain_id: ChainId,
+            asset: Asset,
+            currency_id: Vec<u8>,
+            kind: BalanceKind,
+        ) -> Result<()> {
+            self.force_add_asset(AssetEntry {
+                chain_id,
+                asset,
+                storage_key: StorageKeyLayout::orml_tokens(currency_id),
+                value_layout: BalanceValueLayout::OrmlAccountData,
+                kind,
+                enabled: true,
+            })
+        }
+
+        /// Updates a registered asset
+        #[ink(message)]
+        pub fn force_update_asset(&mut self, entry: AssetEntry) -> Result<()> {
+            self.ensure_root()?;
+
+            entry.asset.validate()?;
+            let key = entry.key();
+            if !self.assets.contains(key) {
+                return Err(Error::AssetNotFound);
+            }
+
+            self.assets.insert(key, &entry);
+            Ok(())
+        }
+
+        /// Removes a registered asset, other than the default one
+        #[ink(message)]
+        pub fn force_remove_asset(&mut self, chain_id: ChainId, asset_id: AssetId) -> Result<()> {
+            self.ensure_root()?;
+
+            let key = (chain_id, asset_id);
+            if key == self.default_asset {
+                return Err(Error::DefaultAssetRemoval);
+            }
+            if !self.assets.contains(key) {
+                return Err(Error::AssetNotFound);
+            }
+
+            self.assets.remove(key);
+            self.balance_tiers.remove(key);
+            self.asset_keys.retain(|k| *k != key);
+            Ok(())
+        }
+
+        /// Updates the asset for which the balance is checked by the single asset messages
         #[ink(message)]
-        pub fn force_update_snapshot(&mut self, snapshot: SnapshotCommitment) -> Result<()> {
+        pub fn force_update_default_asset(
+            &mut self,
+            chain_id: ChainId,
+            asset_id: AssetId,
+        ) -> Result<()> {
             self.ensure_root()?;
 
-            self.snapshot = snapshot;
+            let key = (chain_id, asset_id);
+            if !self.assets.contains(key) {
+                return Err(Error::AssetNotFound);
+            }
+
+            self.default_asset = key;
             Ok(())
         }
 
-        /// Updates the storage key prefix of the balances storage
+        /// Updates the storage key prefix of the balances storage of the default asset
         #[ink(message)]
         pub fn force_update_storage_key_prefix(&mut self, key_prefix: Vec<u8>) -> Result<()> {
             self.ensure_root()?;
 
-            self.storage_key_prefix = key_prefix;
+            let mut entry = self.default_asset_entry();
+            entry.storage_key = StorageKeyLayout::Prefix(key_prefix);
+            self.assets.insert(self.default_asset, &entry);
+            Ok(())
+        }
+
+        /// Updates the layout of the balances storage value of the default asset
+        #[ink(message)]
+        pub fn force_update_value_layout(
+            &mut self,
+            value_layout: BalanceValueLayout,
+        ) -> Result<()> {
+            self.ensure_root()?;
+
+            let mut entry = self.default_asset_entry();
+            entry.value_layout = value_layout;
+            self.assets.insert(self.default_asset, &entry);
+            Ok(())
+        }
+
+        /// Updates the balance thresholds of the tiers of the asset
+        #[ink(message)]
+        pub fn force_update_balance_tiers(
+            &mut self,
+            chain_id: ChainId,
+            asset_id: AssetId,
+            tiers: Vec<Balance>,
+        ) -> Result<()> {
+            self.ensure_root()?;
+
+            let key = (chain_id, asset_id);
+            if !self.assets.contains(key) {
+                return Err(Error::AssetNotFound);
+            }
+
+            self.balance_tiers.insert(key, &tiers);
             Ok(())
         }
 
+        /// Updates the balance to attest for the default asset
+        #[ink(message)]
+        pub fn force_update_balance_kind(&mut self, kind: BalanceKind) -> Result<()> {
+            self.ensure_root()?;
+
+            let mut entry = self.default_asset_entry();
+            entry.kind = kind;
+            self.assets.insert(self.default_asset, &entry);
+            Ok(())
+        }
+
+        /// Updates the info of the default asset, which is registered under the new asset id along with its balance tiers
         #[ink(message)]
         pub fn force_update_asset_info(&mut self, asset_info: Asset) -> Result<()> {
             self.ensure_root()?;
+            asset_info.validate()?;
+
+            let mut entry = self.default_asset_entry();
+            entry.asset = asset_info;
+            let key = entry.key();
+            if key != self.default_asset && self.assets.contains(key) {
+                return Err(Error::AssetAlreadyRegistered);
+            }
 
-            self.asset = asset_info;
+            self.assets.remove(self.default_asset);
+            self.assets.insert(key, &entry);
+            if let Some(tiers) = self.balance_tiers.get(self.default_asset) {
+                self.balance_tiers.remove(self.default_asset);
+                self.balance_tiers.insert(key, &tiers);
+            }
+            for k in self.asset_keys.iter_mut() {
+                if *k == self.default_asset {
+                    *k = key;
+                }
+            }
+            self.default_asset = key;
             Ok(())
         }
 
-        /// Updates the rpc url
+        /// Updates the rpc url of the default chain
         #[ink(message)]
         pub fn force_update_rpc_url(&mut self, url: String) -> Result<()> {
+            self.force_update_chain_rpc_url(self.default_chain_id(), url)
+        }
+
+        /// Updates the rpc url of the chain, which becomes its only endpoint
+        #[ink(message)]
+        pub fn force_update_chain_rpc_url(&mut self, chain_id: ChainId, url: String) -> Result<()> {
+            self.ensure_root()?;
+
+            let mut chain = self.registered_chain(chain_id)?;
+            chain.rpc = Rpc::new(url);
+            self.chains.insert(chain_id, &chain);
+            Ok(())
+        }
+
+        /// Adds an rpc endpoint to the chain, which is requested after the existing ones
+        #[ink(message)]
+        pub fn force_add_chain_rpc_endpoint(
+            &mut self,
+            chain_id: ChainId,
+            url: String,
+        ) -> Result<()> {
+            self.ensure_root()?;
+
+            let mut chain = self.registered_chain(chain_id)?;
+            chain.rpc.add_endpoint(url)?;
+            self.chains.insert(chain_id, &chain);
+            Ok(())
+        }
+
+        /// Removes an rpc endpoint from the chain
+        #[ink(message)]
+        pub fn force_remove_chain_rpc_endpoint(
+            &mut self,
+            chain_id: ChainId,
+            url: String,
+        ) -> Result<()> {
+            self.ensure_root()?;
+
+            let mut chain = self.registered_chain(chain_id)?;
+            chain.rpc.remove_endpoint(&url)?;
+            self.chains.insert(chain_id, &chain);
+            Ok(())
+        }
+
+        /// Updates the number of rpc endpoints of the chain that must return the same result
+        #[ink(message)]
+        pub fn force_update_chain_rpc_quorum(
+            &mut self,
+            chain_id: ChainId,
+            quorum: u32,
+        ) -> Result<()> {
+            self.ensure_root()?;
+
+            let mut chain = self.registered_chain(chain_id)?;
+            chain.rpc.set_quorum(quorum)?;
+            self.chains.insert(chain_id, &chain);
+            Ok(())
+        }
+
+        /// Updates the relay chain rpc url of the default chain
+        #[ink(message)]
+        pub fn force_update_relay_rpc_url(&mut self, url: Option<String>) -> Result<()> {
+            self.force_update_chain_relay_rpc_url(self.default_chain_id(), url)
+        }
+
+        /// Updates the relay chain rpc url of the chain, which becomes its only endpoint
+        #[ink(message)]
+        pub fn force_update_chain_relay_rpc_url(
+            &mut self,
+            chain_id: ChainId,
+            url: Option<String>,
+        ) -> Result<()> {
+            self.ensure_root()?;
+
+            let mut chain = self.registered_chain(chain_id)?;
+            chain.relay_rpc = url.map(Rpc::new);
+            self.chains.insert(chain_id, &chain);
+            Ok(())
+        }
+
+        /// Adds a relay chain rpc endpoint to the chain, which is requested after the existing ones
+        #[ink(message)]
+        pub fn force_add_chain_relay_rpc_endpoint(
+            &mut self,
+            chain_id: ChainId,
+            url: String,
+        ) -> Result<()> {
+            self.ensure_root()?;
+
+            let mut chain = self.registered_chain(chain_id)?;
+            chain
+                .relay_rpc
+                .as_mut()
+                .ok_or(Error::RelayRpcNotSet)?
+                .add_endpoint(url)?;
+            self.chains.insert(chain_id, &chain);
+            Ok(())
+        }
+
+        /// Removes a relay chain rpc endpoint from the chain
+        #[ink(message)]
+        pub fn force_remove_chain_relay_rpc_endpoint(
+            &mut self,
+            chain_id: ChainId,
+            url: String,
+        ) -> Result<()> {
             self.ensure_root()?;
 
-            self.rpc = Rpc::new(url);
+            let mut chain = self.registered_chain(chain_id)?;
+            chain
+                .relay_rpc
+                .as_mut()
+                .ok_or(Error::RelayRpcNotSet)?
+                .remove_endpoint(&url)?;
+            self.chains.insert(chain_id, &chain);
+            Ok(())
+        }
+
+        /// Updates the number of relay chain rpc endpoints of the chain that must return the same result
+        #[ink(message)]
+        pub fn force_update_chain_relay_rpc_quorum(
+            &mut self,
+            chain_id: ChainId,
+            quorum: u32,
+        ) -> Result<()> {
+            self.ensure_root()?;
+
+            let mut chain = self.registered_chain(chain_id)?;
+            chain
+                .relay_rpc
+                .as_mut()
+                .ok_or(Error::RelayRpcNotSet)?
+                .set_quorum(quorum)?;
+            self.chains.insert(chain_id, &chain);
             Ok(())
         }
 
         /// Updates the prover status
         #[ink(message)]
         pub fn force_update_prover_status(&mut self, status: ProverStatus) -> Result<()> {
+            let who = self.ensure_root()?;
+
+            self.status = ProverStatusInfo::new(status, who, self.env().block_timestamp());
+            Ok(())
+        }
+
+        /// Updates the EVM chain and contract that verify the signed requests
+        #[ink(message)]
+        pub fn force_update_evm_target(&mut self, evm_target: EvmTarget) -> Result<()> {
             self.ensure_root()?;
 
-            self.status = status;
+            self.evm_target = evm_target;
             Ok(())
         }
 
-        /// Proves the balance of the caller account on the chain at the state identified by the stored `snapshot`
+        /// Updates the validity (in seconds) of a signed request
+        #[ink(message)]
+        pub fn force_update_signature_ttl(&mut self, ttl: u64) -> Result<()> {
+            self.ensure_root()?;
+
+            self.signature_ttl = ttl;
+            Ok(())
+        }
+
+        /// Updates the scheme used to hash requests before signing them
+        #[ink(message)]
+        pub fn force_update_signature_scheme(&mut self, scheme: SignatureScheme) -> Result<()> {
+            self.ensure_root()?;
+
+            self.signature_scheme = scheme;
+            Ok(())
+        }
+
+        /// Updates the format of the recovery id within the signatures
+        #[ink(message)]
+        pub fn force_update_recovery_id_format(&mut self, format: RecoveryIdFormat) -> Result<()> {
+            self.ensure_root()?;
+
+            self.recovery_id_format = format;
+            Ok(())
+        }
+
+        /// Returns the balance of `account` for the default asset at the state identified by the snapshot of its chain, without signing it
+        #[ink(message)]
+        pub fn balance_of(&self, account: AccountId) -> Result<Balance> {
+            let (chain_id, asset_id) = self.default_asset;
+            self.asset_balance_of(chain_id, asset_id, account)
+        }
+
+        /// Returns the balance of `account` for the asset at the state identified by the snapshot of its chain, without signing it
+        #[ink(message)]
+        pub fn asset_balance_of(
+            &self,
+            chain_id: ChainId,
+            asset_id: AssetId,
+            account: AccountId,
+        ) -> Result<Balance> {
+            self.status.ensure_serving()?;
+
+            let entry = self.enabled_asset((chain_id, asset_id))?;
+            let chain = self.registered_chain(chain_id)?;
+            self.verified_balance(account, &entry, &chain, &chain.rpc)
+        }
+
+        /// Returns the balance of `account` for the default asset, read from the trie nodes of a state proof
+        /// collected off-chain and verified against the snapshot of its chain, without signing it
+        #[ink(message)]
+        pub fn balance_of_with_proof(
+            &self,
+            account: AccountId,
+            storage_proof: Vec<Vec<u8>>,
+        ) -> Result<Balance> {
+            self.status.ensure_serving()?;
+
+            let entry = self.enabled_asset(self.default_asset)?;
+            let chain = self.registered_chain(entry.chain_id)?;
+            self.verified_balance(account, &entry, &chain, &SuppliedProof::new(storage_proof))
+        }
+
+        /// Returns the balances of `account` for the assets of the chain at the state identified by its snapshot,
+        /// read from a single state proof, without signing them
+        #[ink(message)]
+        pub fn asset_balances_of(
+            &self,
+            chain_id: ChainId,
+            asset_ids: Vec<AssetId>,
+            account: AccountId,
+        ) -> Result<Vec<Balance>> {
+            self.status.ensure_serving()?;
+
+            let chain = self.registered_chain(chain_id)?;
+            let entries = asset_ids
+                .into_iter()
+                .map(|asset_id| self.enabled_asset((chain_id, asset_id)))
+                .collect::<Result<Vec<_>>>()?;
+            let storage_keys: Vec<Vec<u8>> = entries
+                .iter()
+                .map(|entry| {
+                    BalanceStorageKeyBuilder::from_layout(
+                        &entry.storage_key,
+                        entry.asset.id(),
+                        account,
+                    )
+                })
+                .collect();
+
+            let values = chain.read_values(&storage_keys, &chain.rpc)?;
+            entries
+                .iter()
+                .zip(values)
+                .map(|(entry, value)| {
+                    let value = value.ok_or(Error::InvalidBalance)?;
+                    entry.value_layout.decode_balance(&value, entry.kind)
+                })
+                .collect()
+        }
+
+        /// Proves the balance of the caller account for the default asset at the state identified by the snapshot of its chain
         #[ink(message)]
         pub fn prove_balance(&self, claim_address: Address) -> Result<BalanceProverMessage> {
+            let (chain_id, asset_id) = self.default_asset;
+            self.prove_asset_balance(chain_id, asset_id, claim_address)
+        }
+
+        /// Proves the balance of the caller account for the asset at the state identified by the snapshot of its chain
+        #[ink(message)]
+        pub fn prove_asset_balance(
+            &self,
+            chain_id: ChainId,
+            asset_id: AssetId,
+            claim_address: Address,
+        ) -> Result<BalanceProverMessage> {
+            self.status.ensure_signing()?;
+            self.ensure_claim_consent_not_required()?;
+
             let who = self.env().caller();
-            // Construct the storage key to retrieve the caller balance amount
-            let storage_key = BalanceStorageKeyBuilder::from_prefix(&self.storage_key_prefix)
-                .push_item_key(StorageItemKey::Blake2_128Concat(who))
-                .build();
-
-            // Retrieve the substrate state proof via RPC
-            let proof = SubstrateStateProof {
-                hasher: self.snapshot.hasher.clone(),
-                storage_proof: self
-                    .rpc
-                    .get_read_proof(&storage_key, &self.snapshot.block_hash)?,
-            };
+            self.sign_balance(who, (chain_id, asset_id), claim_address, None)
+        }
 
-            // Verify the state proof and read the value
-            let value =
-                verifier::verify_state_proof(&self.snapshot.state_root, &storage_key, proof)?
-                    .ok_or(Error::InvalidBalance)?;
-            let amount: Balance =
-                Decode::decode(&mut &*value).map_err(|_| Error::InvalidBalanceDecoding)?;
+        /// Proves the balance of the caller account for the default asset from the trie nodes of a state proof
+        /// collected off-chain, which are verified against the snapshot state root without any RPC request
+        #[ink(message)]
+        pub fn prove_balance_with_proof(
+            &self,
+            claim_address: Address,
+            storage_proof: Vec<Vec<u8>>,
+        ) -> Result<BalanceProverMessage> {
+            let (chain_id, asset_id) = self.default_asset;
+            self.prove_asset_balance_with_proof(chain_id, asset_id, claim_address, storage_proof)
+        }
 
-            // Return the prover message
-            let request = ProverRequest::new(who, claim_address, self.asset.clone(), amount);
-            let prover_message = BalanceProverMessageBuilder::default()
-                .request(request)
-                .sign_request(&self.pair())
-                .build();
+        /// Proves the balance of the caller account for the asset from the trie nodes of a state proof
+        /// collected off-chain, which are verified against the snapshot state root without any RPC request
+        #[ink(message)]
+        pub fn prove_asset_balance_with_proof(
+            &self,
+            chain_id: ChainId,
+            asset_id: AssetId,
+            claim_address: Address,
+            storage_proof: Vec<Vec<u8>>,
+        ) -> Result<BalanceProverMessage> {
+            self.status.ensure_signing()?;
+            self.ensure_claim_consent_not_required()?;
+
+            let who = self.env().caller();
+            self.sign_balance(
+                who,
+                (chain_id, asset_id),
+                claim_address,
+                Some(SuppliedProof::new(storage_proof)),
+            )
+        }
 
-            Ok(prover_message)
+        /// Proves a salted commitment to the balance of the caller account for the default asset.
+        ///
+        /// The amount and the salt that open the commitment are only returned to the caller.
+        #[ink(message)]
+        pub fn prove_balance_commitment(
+            &self,
+            claim_address: Address,
+        ) -> Result<BalanceCommitmentMessage> {
+            let (chain_id, asset_id) = self.default_asset;
+            self.prove_asset_balance_commitment(chain_id, asset_id, claim_address)
+        }
+
+        /// Proves a salted commitment to the balance of the caller account for the asset.
+        ///
+        /// The amount and the salt that open the commitment are only returned to the caller.
+        #[ink(message)]
+        pub fn prove_asset_balance_commitment(
+            &self,
+            chain_id: ChainId,
+            asset_id: AssetId,
+            claim_address: Address,
+        ) -> Result<BalanceCommitmentMessage> {
+            self.status.ensure_signing()?;
+            self.ensure_claim_consent_not_required()?;
+
+            let who = self.env().caller();
+            self.sign_commitment(who, (chain_id, asset_id), claim_address)
+        }
+
+        /// Proves that the balance of the caller account for the default asset meets `threshold`, without revealing it
+        #[ink(message)]
+        pub fn prove_balance_at_least(
+            &self,
+            claim_address: Address,
+            threshold: Balance,
+        ) -> Result<BalanceProverMessage> {
+            let (chain_id, asset_id) = self.default_asset;
+            self.prove_asset_balance_at_least(chain_id, asset_id, claim_address, threshold)
+        }
+
+        /// Proves that the balance of the caller account for the asset meets `threshold`, without revealing it
+        #[ink(message)]
+        pub fn prove_asset_balance_at_least(
+            &self,
+            chain_id: ChainId,
+            asset_id: AssetId,
+            claim_address: Address,
+            threshold: Balance,
+        ) -> Result<BalanceProverMessage> {
+            self.status.ensure_signing()?;
+            self.ensure_claim_consent_not_required()?;
+
+            let who = self.env().caller();
+            let (entry, chain, amount) =
+                self.verified_asset_balance(who, (chain_id, asset_id), None)?;
+            if amount < threshold {
+                return Err(Error::BalanceBelowThreshold);
+            }
+
+            self.sign_threshold(who, claim_address, entry, chain, threshold)
+        }
+
+        /// Proves the highest balance tier of the default asset met by the caller account, without revealing its balance
+        #[ink(message)]
+        pub fn prove_balance_tier(&self, claim_address: Address) -> Result<BalanceProverMessage> {
+            let (chain_id, asset_id) = self.default_asset;
+            self.prove_asset_balance_tier(chain_id, asset_id, claim_address)
+        }
+
+        /// Proves the highest balance tier of the asset met by the caller account, without revealing its balance
+        #[ink(message)]
+        pub fn prove_asset_balance_tier(
+            &self,
+            chain_id: ChainId,
+            asset_id: AssetId,
+            claim_address: Address,
+        ) -> Result<BalanceProverMessage> {
+            self.status.ensure_signing()?;
+            self.ensure_claim_consent_not_required()?;
+
+            let key = (chain_id, asset_id);
+            let tiers = self
+                .balance_tiers
+                .get(key)
+                .ok_or(Error::BalanceTiersNotSet)?;
+
+            let who = self.env().caller();
+            let (entry, chain, amount) = self.verified_asset_balance(who, key, None)?;
+            let tier = tiers
+                .into_iter()
+                .filter(|tier| *tier <= amount)
+                .max()
+                .ok_or(Error::BalanceBelowThreshold)?;
+
+            self.sign_threshold(who, claim_address, entry, chain, tier)
+        }
+
+        /// The payload that `account` signs to let anyone prove its balance of the default asset on its behalf with `prove_balance_for`,
+        /// until `expires_at` (in seconds)
+        #[ink(message)]
+        pub fn delegated_proof_payload(
+            &self,
+            account: AccountId,
+            claim_address: Address,
+            expires_at: u64,
+        ) -> Result<Vec<u8>> {
+            let (chain_id, asset_id) = self.default_asset;
+            self.asset_delegated_proof_payload(
+                chain_id,
+                asset_id,
+                account,
+                claim_address,
+                expires_at,
+            )
+        }
+
+        /// The payload that `account` signs to let anyone prove its balance of the asset on its behalf with `prove_asset_balance_for`,
+        /// until `expires_at` (in seconds)
+        #[ink(message)]
+        pub fn asset_delegated_proof_payload(
+            &self,
+            chain_id: ChainId,
+            asset_id: AssetId,
+            account: AccountId,
+            claim_address: Address,
+            expires_at: u64,
+        ) -> Result<Vec<u8>> {
+            let entry = self.enabled_asset((chain_id, asset_id))?;
+            let chain = self.registered_chain(entry.chain_id)?;
+
+            Ok(DelegatedProofPayload::new(
+                self.env().account_id(),
+                account,
+                claim_address,
+                chain.genesis_hash,
+                asset_id,
+                chain.snapshot.block_hash,
+                expires_at,
+            )
+            .encode())
+        }
+
+        /// Proves the balance of `account` for the default asset on its behalf, given its signature over the `delegated_proof_payload`
+        #[ink(message)]
+        pub fn prove_balance_for(
+            &self,
+            account: AccountId,
+            claim_address: Address,
+            expires_at: u64,
+            signature: Vec<u8>,
+            signer_type: SignerType,
+        ) -> Result<BalanceProverMessage> {
+            let (chain_id, asset_id) = self.default_asset;
+            self.prove_asset_balance_for(
+                chain_id,
+                asset_id,
+                account,
+                claim_address,
+                expires_at,
+                signature,
+                signer_type,
+            )
+        }
+
+        /// Proves the balance of `account` for the asset on its behalf, given its signature over the `asset_delegated_proof_payload`
+        #[ink(message)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn prove_asset_balance_for(
+            &self,
+            chain_id: ChainId,
+            asset_id: AssetId,
+            account: AccountId,
+            claim_address: Address,
+            expires_at: u64,
+            signature: Vec<u8>,
+            signer_type: SignerType,
+        ) -> Result<BalanceProverMessage> {
+            self.status.ensure_signing()?;
+            self.ensure_claim_consent_not_required()?;
+
+            // Block timestamps are in milliseconds
+            if self.env().block_timestamp() / 1000 > expires_at {
+                return Err(Error::DelegatedProofExpired);
+            }
+            let payload = self.asset_delegated_proof_payload(
+                chain_id,
+                asset_id,
+                account,
+                claim_address,
+                expires_at,
+            )?;
+            if !verify_account_signature(&account, &payload, &signature, signer_type) {
+                return Err(Error::InvalidAccountSignature);
+            }
+
+            self.sign_balance(account, (chain_id, asset_id), claim_address, None)
+        }
+
+        /// The payload that the owner of `claim_address` signs with EIP-191 `personal_sign` to consent to claim the balance
+        /// of `account` for the default asset, until `expires_at` (in seconds)
+        #[ink(message)]
+        pub fn claim_consent_payload(
+            &self,
+            account: AccountId,
+            expires_at: u64,
+        ) -> Result<Vec<u8>> {
+            let (chain_id, asset_id) = self.default_asset;
+            self.asset_claim_consent_payload(chain_id, asset_id, account, expires_at)
+        }
+
+        /// The payload that the owner of `claim_address` signs with EIP-191 `personal_sign` to consent to claim the balance
+        /// of `account` for the asset, until `expires_at` (in seconds)
+        #[ink(message)]
+        pub fn asset_claim_consent_payload(
+            &self,
+            chain_id: ChainId,
+            asset_id: AssetId,
+            account: AccountId,
+            expires_at: u64,
+        ) -> Result<Vec<u8>> {
+            let entry = self.enabled_asset((chain_id, asset_id))?;
+            let chain = self.registered_chain(entry.chain_id)?;
+
+            Ok(ClaimConsentPayload::new(
+                self.env().account_id(),
+                account,
+                chain.genesis_hash,
+                asset_id,
+                chain.snapshot.block_hash,
+                expires_at,
+            )
+            .encode())
+        }
+
+        /// Proves the balance of the caller account for the default asset, given the signature of the `claim_consent_payload`
+        /// by `claim_address`
+        #[ink(message)]
+        pub fn prove_balance_with_consent(
+            &self,
+            claim_address: Address,
+            expires_at: u64,
+            consent_signature: Vec<u8>,
+        ) -> Result<BalanceProverMessage> {
+            let (chain_id, asset_id) = self.default_asset;
+            self.prove_asset_balance_with_consent(
+                chain_id,
+                asset_id,
+                claim_address,
+                expires_at,
+                consent_signature,
+            )
+        }
+
+        /// Proves the balance of the caller account for the asset, given the signature of the `asset_claim_consent_payload`
+        /// by `claim_address`
+        #[ink(message)]
+        pub fn prove_asset_balance_with_consent(
+            &self,
+            chain_id: ChainId,
+            asset_id: AssetId,
+            claim_address: Address,
+            expires_at: u64,
+            consent_signature: Vec<u8>,
+        ) -> Result<BalanceProverMessage> {
+            self.status.ensure_signing()?;
+
+            // Block timestamps are in milliseconds
+            if self.env().block_timestamp() / 1000 > expires_at {
+                return Err(Error::ClaimConsentExpired);
+            }
+            let who = self.env().caller();
+            let payload = self.asset_claim_consent_payload(chain_id, asset_id, who, expires_at)?;
+            let digest = eth_signed_message_hash(&payload);
+            if Address::recover(&digest, &consent_signature) != Some(claim_address) {
+                return Err(Error::InvalidClaimConsent);
+            }
+
+            self.sign_balance(who, (chain_id, asset_id), claim_address, None)
         }
     }
 
@@ -226,9 +1540,6 @@ mod balances_prover {
     /// The below code is technically just normal Rust code.
     #[cfg(test)]
     mod tests {
-        /// Imports all the definitions from the outer scope so we can use them here.
-        use super::*;
-
         /// We test a simple use case of our contract.
         #[ink::test]
         fn it_works() {}
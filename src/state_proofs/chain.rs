@@ -0,0 +1,384 @@
+use super::{grandpa, header, parachain, rpc::Rpc, source::ProofSource, verifier};
+use crate::types::{
+    grandpa::AuthoritySet, header::Header, Error, Result, SnapshotBlock, SnapshotCommitment,
+};
+use alloc::vec::Vec;
+use scale::{Decode, Encode};
+
+/// A Substrate chain whose balances are proven
+#[derive(Debug, Encode, Decode, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+)]
+pub struct Chain {
+    /// The genesis hash of the chain, which identifies it within the signed requests
+    pub genesis_hash: Vec<u8>,
+    /// The RPC that handles the read requests of state proofs
+    pub rpc: Rpc,
+    /// The relay chain RPC, for parachain snapshots proven through the relay chain
+    pub relay_rpc: Option<Rpc>,
+    /// The chain snapshot commitment
+    pub snapshot: SnapshotCommitment,
+    /// The trusted GRANDPA authority set of the chain, or of the relay chain for parachain snapshots
+    pub authority_set: Option<AuthoritySet>,
+}
+
+impl Chain {
+    /// Registers a chain at the snapshot block, whose header is checked to hash to the block hash.
+    ///
+    /// The chain has no authority set yet, hence the snapshot, the genesis hash and the state version
+    /// are the ones given by the caller.
+    /// The genesis hash must be 32 bytes long, since it is signed as a `bytes32`.
+    pub fn new(
+        genesis_hash: Vec<u8>,
+        snapshot: SnapshotBlock,
+        rpc: Rpc,
+        relay_rpc: Option<Rpc>,
+    ) -> Result<Self> {
+        verifier::h256_from_slice(&genesis_hash)?;
+
+        Ok(Self {
+            genesis_hash,
+            rpc,
+            relay_rpc,
+            snapshot: Self::verify_snapshot(snapshot)?,
+            authority_set: None,
+        })
+    }
+
+    /// Commits to the snapshot block at its header, which must hash to the block hash,
+    /// and for parachain snapshots through the relay chain block whose state holds the header as the parachain head.
+    ///
+    /// The finality of the block is not verified.
+    pub fn verify_snapshot(block: SnapshotBlock) -> Result<SnapshotCommitment> {
+        let header = Header::new(block.header);
+        let mut snapshot = header::snapshot_at_header(
+            block.block_hash,
+            &header,
+            block.hasher,
+            block.state_version,
+        )?;
+        if let Some(relay_block) = block.relay_block {
+            snapshot.relay_anchor = Some(parachain::verify_parachain_head(&header, relay_block)?);
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Retrieves the block at `height` from the RPCs, along with the evidence to submit it as a snapshot.
+    ///
+    /// For parachain snapshots, `height` is the one of the relay chain block whose parachain head is the snapshot block.
+    /// The state version defaults to the one of the snapshot when the runtime does not declare it.
+    pub fn snapshot_block(&self, height: u32) -> Result<SnapshotBlock> {
+        let (header, relay_block) = match &self.snapshot.relay_anchor {
+            None => {
+                let block_hash = self.rpc.get_block_hash(height)?;
+                (self.rpc.get_header(&block_hash)?, None)
+            }
+            Some(anchor) => {
+                let relay_rpc = self.relay_rpc.as_ref().ok_or(Error::RelayRpcNotSet)?;
+                let (relay_block, header) = parachain::relay_block_at(relay_rpc, anchor, height)?;
+                (header, Some(relay_block))
+            }
+        };
+        let block_hash = header.hash(&self.snapshot.hasher).as_bytes().to_vec();
+        let state_version = self
+            .rpc
+            .get_state_version(&block_hash)?
+            .unwrap_or(self.snapshot.state_version);
+
+        Ok(SnapshotBlock {
+            block_hash,
+            header: header.encode(),
+            hasher: self.snapshot.hasher.clone(),
+            state_version,
+            relay_block,
+        })
+    }
+
+    /// Retrieves the encoded finality proof of the block at `height` from the RPC,
+    /// or from the relay chain RPC for parachain snapshots
+    pub fn finality_proof(&self, height: u32) -> Result<Option<Vec<u8>>> {
+        match &self.snapshot.relay_anchor {
+            None => self.rpc.prove_finality(height),
+            Some(_) => self
+                .relay_rpc
+                .as_ref()
+                .ok_or(Error::RelayRpcNotSet)?
+                .prove_finality(height),
+        }
+    }
+
+    /// Updates the snapshot with a newer one that has been finalized by the trusted authority set.
+    ///
+    /// The finality proof is the one of the snapshot block, or of its relay chain block for parachain snapshots.
+    /// The snapshot must be of the same parachain as the stored one, or of the relay chain itself,
+    /// since the authority set finalizes the heads of every parachain.
+    /// The state versions are not covered by the finality proof, hence the ones of the stored snapshot are kept.
+    pub fn update_snapshot(&mut self, block: SnapshotBlock, finality_proof: &[u8]) -> Result<()> {
+        let set = self
+            .authority_set
+            .as_ref()
+            .ok_or(Error::AuthoritySetNotSet)?;
+        let mut snapshot = Self::verify_snapshot(block)?;
+        let para_id = |snapshot: &SnapshotCommitment| {
+            snapshot.relay_anchor.as_ref().map(|anchor| anchor.para_id)
+        };
+        if para_id(&snapshot) != para_id(&self.snapshot) {
+            return Err(Error::SnapshotChainMismatch);
+        }
+        if snapshot.height <= self.snapshot.height {
+            return Err(Error::SnapshotNotNewer);
+        }
+        Self::verify_finality(&snapshot, set, finality_proof)?;
+
+        snapshot.state_version = self.snapshot.state_version;
+        if let (Some(anchor), Some(stored)) =
+            (&mut snapshot.relay_anchor, &self.snapshot.relay_anchor)
+        {
+            anchor.relay_state_version = stored.relay_state_version;
+        }
+        self.snapshot = snapshot;
+        Ok(())
+    }
+
+    /// Replaces the snapshot with the snapshot block given by sudo, which may be older or of another parachain.
+    ///
+    /// Once the chain has a trusted authority set, the snapshot must be finalized by it,
+    /// otherwise the snapshot is trusted from sudo.
+    pub fn force_update_snapshot(
+        &mut self,
+        block: SnapshotBlock,
+        finality_proof: Option<&[u8]>,
+    ) -> Result<()> {
+        let snapshot = Self::verify_snapshot(block)?;
+        if let Some(set) = &self.authority_set {
+            let finality_proof = finality_proof.ok_or(Error::FinalityProofRequired)?;
+            Self::verify_finality(&snapshot, set, finality_proof)?;
+        }
+
+        self.snapshot = snapshot;
+        Ok(())
+    }
+
+    // Verifies that the snapshot has been finalized by the authority set,
+    // the finality of parachain snapshots being the one of their relay chain block
+    fn verify_finality(
+        snapshot: &SnapshotCommitment,
+        set: &AuthoritySet,
+        finality_proof: &[u8],
+    ) -> Result<()> {
+        match &snapshot.relay_anchor {
+            None => grandpa::verify_snapshot_finality(snapshot, set, finality_proof),
+            Some(anchor) => {
+                grandpa::verify_snapshot_finality(&anchor.relay_snapshot(), set, finality_proof)
+            }
+        }
+    }
+
+    /// Advances the trusted authority set to the one scheduled by a header, once the change is enacted by a finalized block
+    pub fn advance_authority_set(&mut self, header: &Header, finality_proof: &[u8]) -> Result<()> {
+        let set = self
+            .authority_set
+            .as_ref()
+            .ok_or(Error::AuthoritySetNotSet)?;
+        let hasher = match &self.snapshot.relay_anchor {
+            None => &self.snapshot.hasher,
+            Some(anchor) => &anchor.relay_hasher,
+        };
+
+        let next_set = grandpa::verify_authority_set_change(set, header, finality_proof, hasher)?;
+
+        self.authority_set = Some(next_set);
+        Ok(())
+    }
+
+    /// Reads the value stored at `storage_key` from a state proof of the source verified against the snapshot
+    pub fn read_value(
+        &self,
+        storage_key: &[u8],
+        source: &dyn ProofSource,
+    ) -> Result<Option<Vec<u8>>> {
+        verifier::read_verified_value(&self.snapshot, storage_key, source)
+    }
+
+    /// Reads the values stored at `storage_keys` from a single state proof of the source verified against the snapshot
+    pub fn read_values(
+        &self,
+        storage_keys: &[Vec<u8>],
+        source: &dyn ProofSource,
+    ) -> Result<Vec<Option<Vec<u8>>>> {
+        verifier::read_verified_values(&self.snapshot, storage_keys, source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_proofs::{
+        grandpa::tests::{authority_set, finality_proof, header, signed_by, voters},
+        parachain::tests::{relay_block, OTHER_PARA_ID, PARA_ID},
+    };
+    use crate::types::{HashAlgorithm, StateVersion};
+    use alloc::{string::String, vec};
+    use sp_core::H256;
+
+    // The snapshot block of the parachain head at `number`, along with the finality proof of its relay chain block
+    fn para_snapshot(para_id: u32, number: u32, relay_number: u32) -> (SnapshotBlock, Vec<u8>) {
+        let para = header(H256::zero(), number, Vec::new());
+        let (relay_block, relay_header) = relay_block(relay_number, &[(para_id, &para)], para_id);
+        let proof = finality_proof(
+            &relay_header,
+            signed_by(&relay_header, &voters()),
+            Vec::new(),
+        );
+
+        let snapshot = SnapshotBlock {
+            block_hash: para.hash(&HashAlgorithm::Blake2).as_bytes().to_vec(),
+            header: para.encode(),
+            hasher: HashAlgorithm::Blake2,
+            state_version: StateVersion::V1,
+            relay_block: Some(relay_block),
+        };
+        (snapshot, proof)
+    }
+
+    // The snapshot block of the relay chain at `number`, along with its finality proof
+    fn relay_snapshot(number: u32) -> (SnapshotBlock, Vec<u8>) {
+        let relay_header = header(H256::zero(), number, Vec::new());
+        let proof = finality_proof(
+            &relay_header,
+            signed_by(&relay_header, &voters()),
+            Vec::new(),
+        );
+
+        let snapshot = SnapshotBlock {
+            block_hash: relay_header
+                .hash(&HashAlgorithm::Blake2)
+                .as_bytes()
+                .to_vec(),
+            header: relay_header.encode(),
+            hasher: HashAlgorithm::Blake2,
+            state_version: StateVersion::V1,
+            relay_block: None,
+        };
+        (snapshot, proof)
+    }
+
+    fn chain(snapshot: SnapshotBlock) -> Chain {
+        let mut chain = Chain::new(
+            vec![1u8; 32],
+            snapshot,
+            Rpc::new(String::from("http://localhost:9933")),
+            None,
+        )
+        .unwrap();
+        chain.authority_set = Some(authority_set());
+        chain
+    }
+
+    #[test]
+    fn updates_to_a_newer_head_of_the_parachain() {
+        let mut chain = chain(para_snapshot(PARA_ID, 5, 10).0);
+
+        let (snapshot, proof) = para_snapshot(PARA_ID, 6, 11);
+        chain.update_snapshot(snapshot, &proof).unwrap();
+        assert_eq!(chain.snapshot.height, 6);
+        assert_eq!(chain.snapshot.relay_anchor.unwrap().relay_height, 11);
+    }
+
+    #[test]
+    fn updates_keep_the_state_versions() {
+        let mut chain = chain(para_snapshot(PARA_ID, 5, 10).0);
+
+        let (mut snapshot, proof) = para_snapshot(PARA_ID, 6, 11);
+        snapshot.state_version = StateVersion::V0;
+        if let Some(relay_block) = &mut snapshot.relay_block {
+            relay_block.state_version = StateVersion::V0;
+        }
+        chain.update_snapshot(snapshot, &proof).unwrap();
+        assert_eq!(chain.snapshot.state_version, StateVersion::V1);
+        assert_eq!(
+            chain.snapshot.relay_anchor.unwrap().relay_state_version,
+            StateVersion::V1
+        );
+    }
+
+    #[test]
+    fn rejects_snapshots_of_other_chains() {
+        let mut parachain = chain(para_snapshot(PARA_ID, 5, 10).0);
+
+        let (snapshot, proof) = para_snapshot(OTHER_PARA_ID, 50, 11);
+        assert_eq!(
+            parachain.update_snapshot(snapshot, &proof),
+            Err(Error::SnapshotChainMismatch)
+        );
+        let (snapshot, proof) = relay_snapshot(11);
+        assert_eq!(
+            parachain.update_snapshot(snapshot, &proof),
+            Err(Error::SnapshotChainMismatch)
+        );
+
+        let mut relay_chain = chain(relay_snapshot(10).0);
+        let (snapshot, proof) = para_snapshot(PARA_ID, 50, 11);
+        assert_eq!(
+            relay_chain.update_snapshot(snapshot, &proof),
+            Err(Error::SnapshotChainMismatch)
+        );
+    }
+
+    #[test]
+    fn force_updates_require_finality_once_the_authority_set_is_trusted() {
+        let mut chain = chain(relay_snapshot(10).0);
+
+        let (snapshot, proof) = relay_snapshot(8);
+        assert_eq!(
+            chain.force_update_snapshot(snapshot.clone(), None),
+            Err(Error::FinalityProofRequired)
+        );
+        let (_, other_proof) = relay_snapshot(9);
+        assert_eq!(
+            chain.force_update_snapshot(snapshot.clone(), Some(&other_proof)),
+            Err(Error::SnapshotNotFinalized)
+        );
+
+        // Sudo may roll the snapshot back
+        chain.force_update_snapshot(snapshot, Some(&proof)).unwrap();
+        assert_eq!(chain.snapshot.height, 8);
+
+        // Without an authority set, the snapshot is trusted from sudo
+        chain.authority_set = None;
+        chain
+            .force_update_snapshot(relay_snapshot(7).0, None)
+            .unwrap();
+        assert_eq!(chain.snapshot.height, 7);
+    }
+
+    #[test]
+    fn rejects_genesis_hashes_that_are_not_32_bytes() {
+        for genesis_hash in [vec![1u8; 31], vec![1u8; 33], Vec::new()] {
+            assert_eq!(
+                Chain::new(
+                    genesis_hash,
+                    relay_snapshot(10).0,
+                    Rpc::new(String::from("http://localhost:9933")),
+                    None,
+                )
+                .err(),
+                Some(Error::InvalidHashBytes)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_older_snapshots() {
+        let mut chain = chain(relay_snapshot(10).0);
+
+        let (snapshot, proof) = relay_snapshot(10);
+        assert_eq!(
+            chain.update_snapshot(snapshot, &proof),
+            Err(Error::SnapshotNotNewer)
+        );
+    }
+}
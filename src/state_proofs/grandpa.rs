@@ -0,0 +1,491 @@
+use super::verifier::h256_from_slice;
+use crate::types::{
+    grandpa::{AuthoritySet, ConsensusLog, FinalityProof, GrandpaJustification, GRANDPA_ENGINE_ID},
+    header::{DigestItem, Header},
+    Error, HashAlgorithm, Result, SnapshotCommitment,
+};
+use alloc::collections::{BTreeMap, BTreeSet};
+use scale::{Decode, Encode};
+use sp_core::{ed25519, Pair, H256};
+
+/// The index of the precommit variant of a GRANDPA message
+const PRECOMMIT_MESSAGE_INDEX: u8 = 1;
+
+/// Verifies that the snapshot block has been finalized by the authority set.
+///
+/// The finality proof is the SCALE encoded `FinalityProof` returned by `grandpa_proveFinality`,
+/// which may justify a descendant of the snapshot block.
+pub fn verify_snapshot_finality(
+    snapshot: &SnapshotCommitment,
+    set: &AuthoritySet,
+    finality_proof: &[u8],
+) -> Result<()> {
+    let snapshot_hash = h256_from_slice(&snapshot.block_hash)?;
+    verify_finality(set, snapshot_hash, finality_proof, &snapshot.hasher)?;
+
+    Ok(())
+}
+
+/// Verifies the authority set change scheduled by the header and returns the next authority set once it is enacted.
+///
+/// A change scheduled with a delay is enacted by the block `delay` blocks after the header, hence the finality
+/// proof returned by `grandpa_proveFinality` for the header must justify that block or one of its descendants.
+/// Forced changes are rejected, since they are not finalized by the current authority set.
+/// A justification by the current set also finalizes the headers that scheduled the previous sets,
+/// hence only the headers after the block that enacted the current set are accepted.
+pub fn verify_authority_set_change(
+    set: &AuthoritySet,
+    header: &Header,
+    finality_proof: &[u8],
+    hasher: &HashAlgorithm,
+) -> Result<AuthoritySet> {
+    let number = header.fields()?.number;
+    if number <= set.enacted_at {
+        return Err(Error::AuthoritySetChangeAlreadyApplied);
+    }
+    let finalized_depth = verify_finality(set, header.hash(hasher), finality_proof, hasher)?;
+
+    let change = header
+        .digest()?
+        .logs
+        .iter()
+        .find_map(|log| match log {
+            DigestItem::Consensus(engine_id, data) if *engine_id == GRANDPA_ENGINE_ID => {
+                match ConsensusLog::decode(&mut &data[..]) {
+                    Ok(ConsensusLog::ScheduledChange(change)) => Some(Ok(change)),
+                    Ok(ConsensusLog::ForcedChange(..)) => {
+                        Some(Err(Error::ForcedAuthoritySetChange))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .ok_or(Error::NoAuthoritySetChange)??;
+
+    if finalized_depth < change.delay {
+        return Err(Error::AuthoritySetChangeNotEnacted);
+    }
+
+    let next_set = AuthoritySet {
+        set_id: set.set_id.saturating_add(1),
+        authorities: change.next_authorities,
+        enacted_at: number.saturating_add(change.delay),
+    };
+    next_set.validate()?;
+
+    Ok(next_set)
+}
+
+// Verifies that the block has been finalized by the authority set and returns the number of blocks
+// from it to the justified block
+fn verify_finality(
+    set: &AuthoritySet,
+    block: H256,
+    finality_proof: &[u8],
+    hasher: &HashAlgorithm,
+) -> Result<u32> {
+    let proof =
+        FinalityProof::decode(&mut &*finality_proof).or(Err(Error::InvalidFinalityProof))?;
+    let justification = GrandpaJustification::decode(&mut &*proof.justification)
+        .or(Err(Error::InvalidFinalityProof))?;
+
+    if justification.commit.target_hash != proof.block {
+        return Err(Error::JustificationTargetMismatch);
+    }
+    verify_justification(set, &justification, hasher)?;
+
+    // The block must be the justified block or one of its ancestors
+    let ancestry = ancestry_of(&proof.unknown_headers, hasher)?;
+    distance_between(&ancestry, block, proof.block).ok_or(Error::SnapshotNotFinalized)
+}
+
+/// Verifies that the justification is signed by more than 2/3 of the authority set weight
+pub fn verify_justification(
+    set: &AuthoritySet,
+    justification: &GrandpaJustification,
+    hasher: &HashAlgorithm,
+) -> Result<()> {
+    set.validate()?;
+    let commit = &justification.commit;
+    let ancestry = ancestry_of(&justification.votes_ancestries, hasher)?;
+
+    let mut voters = BTreeSet::new();
+    let mut signed_weight = 0u64;
+    for signed in commit.precommits.iter() {
+        // Equivocations do not add any weight
+        if !voters.insert(signed.id) {
+            continue;
+        }
+        let weight = set
+            .weight_of(&signed.id)
+            .ok_or(Error::UnknownGrandpaAuthority)?;
+
+        // The signed payload is `(Message::Precommit(precommit), round, set_id)`
+        let payload = (
+            PRECOMMIT_MESSAGE_INDEX,
+            &signed.precommit,
+            justification.round,
+            set.set_id,
+        )
+            .encode();
+        let signature = ed25519::Signature::from_raw(signed.signature);
+        let public = ed25519::Public::from_raw(signed.id);
+        if !ed25519::Pair::verify(&signature, payload, &public) {
+            return Err(Error::InvalidGrandpaSignature);
+        }
+
+        // Every precommit must vote for the commit target or one of its descendants
+        if !is_descendant(&ancestry, commit.target_hash, signed.precommit.target_hash) {
+            return Err(Error::InvalidJustification);
+        }
+
+        signed_weight = signed_weight.saturating_add(weight);
+    }
+
+    if signed_weight < set.threshold() {
+        return Err(Error::InsufficientGrandpaVotes);
+    }
+
+    Ok(())
+}
+
+// Maps the hash of each header to the hash of its parent
+fn ancestry_of(headers: &[Header], hasher: &HashAlgorithm) -> Result<BTreeMap<H256, H256>> {
+    headers
+        .iter()
+        .map(|header| Ok((header.hash(hasher), header.fields()?.parent_hash)))
+        .collect()
+}
+
+// Whether `block` is `base` or one of its descendants according to the ancestry
+fn is_descendant(ancestry: &BTreeMap<H256, H256>, base: H256, block: H256) -> bool {
+    distance_between(ancestry, base, block).is_some()
+}
+
+// The number of blocks from `base` to `block`, if `block` is `base` or one of its descendants according to the ancestry
+fn distance_between(ancestry: &BTreeMap<H256, H256>, base: H256, block: H256) -> Option<u32> {
+    let mut current = block;
+    // Each step must go through a known header, hence the path is at most as long as the ancestry
+    for distance in 0..=ancestry.len() {
+        if current == base {
+            return Some(distance as u32);
+        }
+        match ancestry.get(&current) {
+            Some(parent) => current = *parent,
+            None => return None,
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::types::{
+        grandpa::{Commit, Precommit, ScheduledChange, SignedPrecommit},
+        header::{Digest, HeaderFields},
+        StateVersion,
+    };
+    use alloc::{vec, vec::Vec};
+    use core::ops::RangeInclusive;
+
+    const SET_ID: u64 = 3;
+    const ROUND: u64 = 7;
+
+    fn pairs(seeds: RangeInclusive<u8>) -> Vec<ed25519::Pair> {
+        seeds
+            .map(|seed| ed25519::Pair::from_seed(&[seed; 32]))
+            .collect()
+    }
+
+    pub(crate) fn voters() -> Vec<ed25519::Pair> {
+        pairs(1..=4)
+    }
+
+    // The voters of the set scheduled by the changes
+    fn next_voters() -> Vec<ed25519::Pair> {
+        pairs(11..=14)
+    }
+
+    pub(crate) fn authority_set() -> AuthoritySet {
+        AuthoritySet {
+            set_id: SET_ID,
+            authorities: voters().iter().map(|pair| (pair.public().0, 1)).collect(),
+            enacted_at: 0,
+        }
+    }
+
+    pub(crate) fn header(parent_hash: H256, number: u32, logs: Vec<DigestItem>) -> Header {
+        let fields = HeaderFields {
+            parent_hash,
+            number,
+            state_root: H256::repeat_byte(number as u8),
+            extrinsics_root: H256::zero(),
+        };
+        Header::new((fields, Digest { logs }).encode())
+    }
+
+    fn precommit(pair: &ed25519::Pair, target: &Header, set_id: u64) -> SignedPrecommit {
+        let precommit = Precommit {
+            target_hash: target.hash(&HashAlgorithm::Blake2),
+            target_number: target.fields().unwrap().number,
+        };
+        let payload = (PRECOMMIT_MESSAGE_INDEX, &precommit, ROUND, set_id).encode();
+
+        SignedPrecommit {
+            precommit,
+            signature: pair.sign(&payload).0,
+            id: pair.public().0,
+        }
+    }
+
+    // The encoded finality proof of `target` signed by the precommits, with the headers from the requested block
+    pub(crate) fn finality_proof(
+        target: &Header,
+        precommits: Vec<SignedPrecommit>,
+        unknown_headers: Vec<Header>,
+    ) -> Vec<u8> {
+        let target_hash = target.hash(&HashAlgorithm::Blake2);
+        let justification = GrandpaJustification {
+            round: ROUND,
+            commit: Commit {
+                target_hash,
+                target_number: target.fields().unwrap().number,
+                precommits,
+            },
+            votes_ancestries: Vec::new(),
+        };
+
+        FinalityProof {
+            block: target_hash,
+            justification: justification.encode(),
+            unknown_headers,
+        }
+        .encode()
+    }
+
+    pub(crate) fn signed_by(target: &Header, voters: &[ed25519::Pair]) -> Vec<SignedPrecommit> {
+        voters
+            .iter()
+            .map(|pair| precommit(pair, target, SET_ID))
+            .collect()
+    }
+
+    fn snapshot_of(header: &Header) -> SnapshotCommitment {
+        let fields = header.fields().unwrap();
+        SnapshotCommitment {
+            height: fields.number,
+            block_hash: header.hash(&HashAlgorithm::Blake2).as_bytes().to_vec(),
+            state_root: fields.state_root.as_bytes().to_vec(),
+            hasher: HashAlgorithm::Blake2,
+            state_version: StateVersion::V1,
+            relay_anchor: None,
+        }
+    }
+
+    fn grandpa_log(log: ConsensusLog) -> DigestItem {
+        DigestItem::Consensus(GRANDPA_ENGINE_ID, log.encode())
+    }
+
+    fn next_authorities() -> Vec<([u8; 32], u64)> {
+        next_voters()
+            .iter()
+            .map(|pair| (pair.public().0, 1))
+            .collect()
+    }
+
+    fn scheduled_change(delay: u32) -> DigestItem {
+        grandpa_log(ConsensusLog::ScheduledChange(ScheduledChange {
+            next_authorities: next_authorities(),
+            delay,
+        }))
+    }
+
+    #[test]
+    fn verifies_the_finality_of_the_snapshot_through_a_descendant() {
+        let snapshot = header(H256::zero(), 10, Vec::new());
+        let justified = header(snapshot.hash(&HashAlgorithm::Blake2), 11, Vec::new());
+        let proof = finality_proof(
+            &justified,
+            signed_by(&justified, &voters()[..3]),
+            vec![justified.clone()],
+        );
+
+        assert_eq!(
+            verify_snapshot_finality(&snapshot_of(&snapshot), &authority_set(), &proof),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn rejects_blocks_that_are_not_ancestors_of_the_justified_block() {
+        let snapshot = header(H256::zero(), 10, Vec::new());
+        let fork = header(H256::repeat_byte(1), 11, Vec::new());
+        let proof = finality_proof(&fork, signed_by(&fork, &voters()), vec![fork.clone()]);
+
+        assert_eq!(
+            verify_snapshot_finality(&snapshot_of(&snapshot), &authority_set(), &proof),
+            Err(Error::SnapshotNotFinalized)
+        );
+    }
+
+    #[test]
+    fn requires_more_than_two_thirds_of_the_weight() {
+        let snapshot = header(H256::zero(), 10, Vec::new());
+        let voters = voters();
+
+        let proof = finality_proof(&snapshot, signed_by(&snapshot, &voters[..2]), Vec::new());
+        assert_eq!(
+            verify_snapshot_finality(&snapshot_of(&snapshot), &authority_set(), &proof),
+            Err(Error::InsufficientGrandpaVotes)
+        );
+
+        // Equivocations do not add any weight
+        let mut precommits = signed_by(&snapshot, &voters[..2]);
+        precommits.push(precommits[0].clone());
+        let proof = finality_proof(&snapshot, precommits, Vec::new());
+        assert_eq!(
+            verify_snapshot_finality(&snapshot_of(&snapshot), &authority_set(), &proof),
+            Err(Error::InsufficientGrandpaVotes)
+        );
+    }
+
+    #[test]
+    fn rejects_precommits_of_other_sets_and_authorities() {
+        let snapshot = header(H256::zero(), 10, Vec::new());
+        let voters = voters();
+
+        let mut precommits = signed_by(&snapshot, &voters[..2]);
+        precommits.push(precommit(&voters[2], &snapshot, SET_ID + 1));
+        let proof = finality_proof(&snapshot, precommits, Vec::new());
+        assert_eq!(
+            verify_snapshot_finality(&snapshot_of(&snapshot), &authority_set(), &proof),
+            Err(Error::InvalidGrandpaSignature)
+        );
+
+        let mut precommits = signed_by(&snapshot, &voters[..3]);
+        let outsider = ed25519::Pair::from_seed(&[5u8; 32]);
+        precommits.push(precommit(&outsider, &snapshot, SET_ID));
+        let proof = finality_proof(&snapshot, precommits, Vec::new());
+        assert_eq!(
+            verify_snapshot_finality(&snapshot_of(&snapshot), &authority_set(), &proof),
+            Err(Error::UnknownGrandpaAuthority)
+        );
+    }
+
+    #[test]
+    fn applies_a_scheduled_change_once_enacted() {
+        let change = header(H256::zero(), 10, vec![scheduled_change(1)]);
+        let enacted = header(change.hash(&HashAlgorithm::Blake2), 11, Vec::new());
+        let set = authority_set();
+
+        let proof = finality_proof(&change, signed_by(&change, &voters()), Vec::new());
+        assert_eq!(
+            verify_authority_set_change(&set, &change, &proof, &HashAlgorithm::Blake2).err(),
+            Some(Error::AuthoritySetChangeNotEnacted)
+        );
+
+        let proof = finality_proof(
+            &enacted,
+            signed_by(&enacted, &voters()),
+            vec![enacted.clone()],
+        );
+        let next_set =
+            verify_authority_set_change(&set, &change, &proof, &HashAlgorithm::Blake2).unwrap();
+        assert_eq!(next_set.set_id, SET_ID + 1);
+        assert_eq!(next_set.authorities, next_authorities());
+        assert_eq!(next_set.enacted_at, 11);
+    }
+
+    #[test]
+    fn rejects_replayed_and_older_changes() {
+        let older = header(H256::zero(), 4, vec![scheduled_change(0)]);
+        let change = header(
+            older.hash(&HashAlgorithm::Blake2),
+            10,
+            vec![scheduled_change(1)],
+        );
+        let enacted = header(change.hash(&HashAlgorithm::Blake2), 11, Vec::new());
+        let proof = finality_proof(
+            &enacted,
+            signed_by(&enacted, &voters()),
+            vec![enacted.clone()],
+        );
+        let next_set =
+            verify_authority_set_change(&authority_set(), &change, &proof, &HashAlgorithm::Blake2)
+                .unwrap();
+
+        // A justification by the next set also finalizes the headers that scheduled the previous sets
+        let later = header(enacted.hash(&HashAlgorithm::Blake2), 12, Vec::new());
+        let precommits = next_voters()
+            .iter()
+            .map(|pair| precommit(pair, &later, SET_ID + 1))
+            .collect();
+        let proof = finality_proof(
+            &later,
+            precommits,
+            vec![later.clone(), enacted.clone(), change.clone()],
+        );
+        for replayed in [&change, &older] {
+            assert_eq!(
+                verify_authority_set_change(&next_set, replayed, &proof, &HashAlgorithm::Blake2)
+                    .err(),
+                Some(Error::AuthoritySetChangeAlreadyApplied)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_empty_authority_sets() {
+        let snapshot = header(H256::zero(), 10, Vec::new());
+        let proof = finality_proof(&snapshot, Vec::new(), Vec::new());
+
+        for authorities in [Vec::new(), vec![([1u8; 32], 0)]] {
+            let set = AuthoritySet {
+                set_id: SET_ID,
+                authorities,
+                enacted_at: 0,
+            };
+            assert_eq!(
+                verify_snapshot_finality(&snapshot_of(&snapshot), &set, &proof),
+                Err(Error::EmptyAuthoritySet)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_forced_and_missing_changes() {
+        let forced = header(
+            H256::zero(),
+            10,
+            vec![grandpa_log(ConsensusLog::ForcedChange(
+                8,
+                ScheduledChange {
+                    next_authorities: next_authorities(),
+                    delay: 0,
+                },
+            ))],
+        );
+        let proof = finality_proof(&forced, signed_by(&forced, &voters()), Vec::new());
+        assert_eq!(
+            verify_authority_set_change(&authority_set(), &forced, &proof, &HashAlgorithm::Blake2)
+                .err(),
+            Some(Error::ForcedAuthoritySetChange)
+        );
+
+        let unchanged = header(H256::zero(), 10, Vec::new());
+        let proof = finality_proof(&unchanged, signed_by(&unchanged, &voters()), Vec::new());
+        assert_eq!(
+            verify_authority_set_change(
+                &authority_set(),
+                &unchanged,
+                &proof,
+                &HashAlgorithm::Blake2
+            )
+            .err(),
+            Some(Error::NoAuthoritySetChange)
+        );
+    }
+}
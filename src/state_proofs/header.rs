@@ -0,0 +1,29 @@
+use crate::types::{
+    header::Header, Error, HashAlgorithm, Result, SnapshotCommitment, StateVersion,
+};
+use alloc::vec::Vec;
+
+/// Commits to the snapshot of the block, whose height and state root are read from its header.
+///
+/// The header is checked to hash to the block hash, hence no network access is needed.
+/// This does not prove that the block is of the chain, which is proven by its finality.
+pub fn snapshot_at_header(
+    block_hash: Vec<u8>,
+    header: &Header,
+    hasher: HashAlgorithm,
+    state_version: StateVersion,
+) -> Result<SnapshotCommitment> {
+    if header.hash(&hasher).as_bytes() != block_hash.as_slice() {
+        return Err(Error::SnapshotBlockHashMismatch);
+    }
+    let fields = header.fields()?;
+
+    Ok(SnapshotCommitment {
+        height: fields.number,
+        block_hash,
+        state_root: fields.state_root.as_bytes().to_vec(),
+        hasher,
+        state_version,
+        relay_anchor: None,
+    })
+}
@@ -1,2 +1,7 @@
+pub mod chain;
+pub mod grandpa;
+pub mod header;
+pub mod parachain;
 pub mod rpc;
+pub mod source;
 pub mod verifier;
@@ -0,0 +1,215 @@
+use super::{
+    header,
+    rpc::Rpc,
+    source::{ProofSource, SuppliedProof},
+    verifier,
+};
+use crate::types::{
+    header::Header, Error, RelayBlock, RelayChainAnchor, Result, SnapshotCommitment,
+};
+use crate::utils::balances::{BalanceStorageKeyBuilder, StorageItemKey};
+use alloc::vec::Vec;
+use scale::{Decode, Encode};
+
+/// Verifies a parachain header through the relay chain `Paras::Heads` storage and returns the anchor of the relay chain block.
+///
+/// The relay chain snapshot is taken at the relay chain header, then the parachain head is read from
+/// the supplied relay chain read proof and must be the parachain header.
+pub fn verify_parachain_head(header: &Header, relay_block: RelayBlock) -> Result<RelayChainAnchor> {
+    let relay_header = Header::new(relay_block.header);
+    let relay_snapshot = header::snapshot_at_header(
+        relay_block.block_hash,
+        &relay_header,
+        relay_block.hasher,
+        relay_block.state_version,
+    )?;
+
+    let head_proof = SuppliedProof::new(relay_block.head_proof);
+    let head_data = read_parachain_head(&relay_snapshot, relay_block.para_id, &head_proof)?;
+    if Header::new(head_data) != *header {
+        return Err(Error::ParachainHeadMismatch);
+    }
+
+    Ok(RelayChainAnchor {
+        para_id: relay_block.para_id,
+        relay_height: relay_snapshot.height,
+        relay_block_hash: relay_snapshot.block_hash,
+        relay_state_root: relay_snapshot.state_root,
+        relay_hasher: relay_snapshot.hasher,
+        relay_state_version: relay_snapshot.state_version,
+    })
+}
+
+/// Retrieves the relay chain block at `height` from the relay chain RPC, along with the read proof of the parachain head,
+/// and returns it with the parachain head header.
+///
+/// The relay chain state version defaults to the one of the anchor when the runtime does not declare it.
+pub fn relay_block_at(
+    relay_rpc: &Rpc,
+    anchor: &RelayChainAnchor,
+    height: u32,
+) -> Result<(RelayBlock, Header)> {
+    let block_hash = relay_rpc.get_block_hash(height)?;
+    let relay_header = relay_rpc.get_header(&block_hash)?;
+    let state_version = relay_rpc
+        .get_state_version(&block_hash)?
+        .unwrap_or(anchor.relay_state_version);
+    let relay_snapshot = header::snapshot_at_header(
+        block_hash.clone(),
+        &relay_header,
+        anchor.relay_hasher.clone(),
+        state_version,
+    )?;
+
+    // Endpoints must agree on the parachain head and on the proof of it
+    let (head_data, head_proof) = relay_rpc.get_read_proof_multi(
+        &[parachain_head_key(anchor.para_id)],
+        &block_hash,
+        |proof| {
+            let head_proof = SuppliedProof::new(proof.clone());
+            let head_data = read_parachain_head(&relay_snapshot, anchor.para_id, &head_proof)?;
+            Ok((head_data, proof))
+        },
+    )?;
+
+    let relay_block = RelayBlock {
+        para_id: anchor.para_id,
+        block_hash,
+        header: relay_header.encode(),
+        hasher: anchor.relay_hasher.clone(),
+        state_version,
+        head_proof,
+    };
+    Ok((relay_block, Header::new(head_data)))
+}
+
+// The key of the parachain head in the relay chain `Paras::Heads` storage
+fn parachain_head_key(para_id: u32) -> Vec<u8> {
+    BalanceStorageKeyBuilder::from_storage_item("Paras", "Heads")
+        .push_item_key(StorageItemKey::Twox64Concat(para_id))
+        .build()
+}
+
+/// Reads the head of the parachain from a relay chain state proof
+fn read_parachain_head(
+    relay_snapshot: &SnapshotCommitment,
+    para_id: u32,
+    head_proof: &dyn ProofSource,
+) -> Result<Vec<u8>> {
+    let value =
+        verifier::read_verified_value(relay_snapshot, &parachain_head_key(para_id), head_proof)?
+            .ok_or(Error::ParachainHeadNotFound)?;
+
+    // The stored value is the SCALE encoded `HeadData(Vec<u8>)`
+    Vec::<u8>::decode(&mut &*value).or(Err(Error::InvalidParachainHead))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::state_proofs::grandpa::tests::header;
+    use crate::types::{
+        crypto::hasher::ContractBlakeTwo256,
+        header::{Digest, HeaderFields},
+        HashAlgorithm, StateVersion,
+    };
+    use sp_core::H256;
+    use sp_trie::{LayoutV1, MemoryDB, TrieDBMutBuilder, TrieMut};
+
+    pub(crate) const PARA_ID: u32 = 2000;
+    pub(crate) const OTHER_PARA_ID: u32 = 3000;
+
+    // The relay chain block at `number` whose state holds the heads of the parachains,
+    // submitted with the read proof of the head of `para_id`
+    pub(crate) fn relay_block(
+        number: u32,
+        heads: &[(u32, &Header)],
+        para_id: u32,
+    ) -> (RelayBlock, Header) {
+        let mut db = MemoryDB::<ContractBlakeTwo256>::default();
+        let mut root = H256::zero();
+        {
+            let mut trie =
+                TrieDBMutBuilder::<LayoutV1<ContractBlakeTwo256>>::new(&mut db, &mut root).build();
+            for (id, head) in heads {
+                // The stored value is the SCALE encoded `HeadData(Vec<u8>)`
+                trie.insert(&parachain_head_key(*id), &head.encode().encode())
+                    .unwrap();
+            }
+        }
+        let fields = HeaderFields {
+            parent_hash: H256::zero(),
+            number,
+            state_root: root,
+            extrinsics_root: H256::zero(),
+        };
+        let relay_header = Header::new((fields, Digest { logs: Vec::new() }).encode());
+        // The whole relay chain state is a valid proof of any of its keys
+        let head_proof = db
+            .drain()
+            .into_values()
+            .filter(|(_, rc)| *rc > 0)
+            .map(|(node, _)| node)
+            .collect();
+
+        let relay_block = RelayBlock {
+            para_id,
+            block_hash: relay_header
+                .hash(&HashAlgorithm::Blake2)
+                .as_bytes()
+                .to_vec(),
+            header: relay_header.encode(),
+            hasher: HashAlgorithm::Blake2,
+            state_version: StateVersion::V1,
+            head_proof,
+        };
+        (relay_block, relay_header)
+    }
+
+    #[test]
+    fn verifies_the_parachain_head() {
+        let para = header(H256::zero(), 5, Vec::new());
+        let other = header(H256::zero(), 8, Vec::new());
+        let (relay_block, relay_header) =
+            relay_block(10, &[(PARA_ID, &para), (OTHER_PARA_ID, &other)], PARA_ID);
+
+        let anchor = verify_parachain_head(&para, relay_block).unwrap();
+        assert_eq!(anchor.para_id, PARA_ID);
+        assert_eq!(anchor.relay_height, 10);
+        assert_eq!(
+            anchor.relay_block_hash,
+            relay_header.hash(&HashAlgorithm::Blake2).as_bytes()
+        );
+    }
+
+    #[test]
+    fn rejects_heads_of_other_parachains() {
+        let para = header(H256::zero(), 5, Vec::new());
+        let other = header(H256::zero(), 8, Vec::new());
+        let heads = [(PARA_ID, &para), (OTHER_PARA_ID, &other)];
+
+        let (block, _) = relay_block(10, &heads, PARA_ID);
+        assert_eq!(
+            verify_parachain_head(&other, block).err(),
+            Some(Error::ParachainHeadMismatch)
+        );
+
+        let (block, _) = relay_block(10, &heads, 4000);
+        assert_eq!(
+            verify_parachain_head(&para, block).err(),
+            Some(Error::ParachainHeadNotFound)
+        );
+    }
+
+    #[test]
+    fn rejects_relay_blocks_that_do_not_hash_to_their_hash() {
+        let para = header(H256::zero(), 5, Vec::new());
+        let (mut relay_block, _) = relay_block(10, &[(PARA_ID, &para)], PARA_ID);
+        relay_block.block_hash = H256::repeat_byte(1).as_bytes().to_vec();
+
+        assert_eq!(
+            verify_parachain_head(&para, relay_block).err(),
+            Some(Error::SnapshotBlockHashMismatch)
+        );
+    }
+}
@@ -1,40 +1,271 @@
-use crate::types::{rpc::ReadProof, Error, Result};
+use crate::types::{
+    header::{Header, HeaderFields},
+    rpc::{ReadProofAtBlock, RpcHeader, RuntimeVersion},
+    Error, Result, StateVersion,
+};
 use crate::utils;
-use alloc::{format, string::String, vec::Vec};
+use alloc::{format, string::String, vec, vec::Vec};
+use scale::{Compact, Decode, Encode};
+use sp_core::H256;
 
-/// The RPC that handles read proofs requests
-#[derive(Debug)]
-#[ink::storage_item]
+/// The RPC that handles read proofs requests.
+///
+/// Requests fail over to the next endpoint, in order, on transport or decoding errors.
+/// With a quorum greater than one, `quorum` endpoints must return the same result.
+#[derive(Debug, Encode, Decode, Clone, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
 pub struct Rpc {
-    pub url: String,
+    endpoints: Vec<String>,
+    quorum: u32,
 }
 
 impl Rpc {
     pub fn new(url: String) -> Self {
-        Self { url }
+        Self {
+            endpoints: vec![url],
+            quorum: 1,
+        }
     }
 
-    pub fn get_read_proof(&self, secure_storage_key: &[u8], at: &[u8]) -> Result<Vec<Vec<u8>>> {
-        let storage_key = format!("0x{}", utils::rpc::encode_to_hex(secure_storage_key));
-        let at = format!("0x{}", utils::rpc::encode_to_hex(at));
+    /// The endpoint that is requested first
+    pub fn url(&self) -> String {
+        self.endpoints[0].clone()
+    }
+
+    /// The endpoints, in the order they are requested
+    pub fn endpoints(&self) -> Vec<String> {
+        self.endpoints.clone()
+    }
+
+    /// The number of endpoints that must return the same result
+    pub fn quorum(&self) -> u32 {
+        self.quorum
+    }
+
+    /// Adds an endpoint, which is requested after the existing ones
+    pub fn add_endpoint(&mut self, url: String) -> Result<()> {
+        if self.endpoints.contains(&url) {
+            return Err(Error::RpcEndpointAlreadyAdded);
+        }
+
+        self.endpoints.push(url);
+        Ok(())
+    }
+
+    /// Removes an endpoint, as long as the quorum can still be reached
+    pub fn remove_endpoint(&mut self, url: &str) -> Result<()> {
+        let index = self
+            .endpoints
+            .iter()
+            .position(|endpoint| endpoint == url)
+            .ok_or(Error::RpcEndpointNotFound)?;
+        if self.endpoints.len() <= self.quorum as usize {
+            return Err(Error::InvalidRpcQuorum);
+        }
+
+        self.endpoints.remove(index);
+        Ok(())
+    }
 
-        let data = format!(
-            r#"{{"id":1,"jsonrpc":"2.0","method":"state_getReadProof","params":[["{}"], "{}"]}}"#,
-            storage_key, at
-        )
-        .into_bytes();
-
-        let resp_body = utils::rpc::call_rpc(&self.url, data)?;
-        let (response_proof, _): (ReadProof, usize) =
-            serde_json_core::from_slice(&resp_body).or(Err(Error::RpcInvalidBody))?;
-
-        // construct the proof
-        let mut proof = Vec::new();
-        for hex_str in response_proof.result.proof.into_iter() {
-            let trie_node_hash = utils::rpc::extract_hex_from(&hex_str)?;
-            proof.push(trie_node_hash);
+    /// Sets the number of endpoints that must return the same result
+    pub fn set_quorum(&mut self, quorum: u32) -> Result<()> {
+        if quorum == 0 || quorum as usize > self.endpoints.len() {
+            return Err(Error::InvalidRpcQuorum);
         }
 
-        Ok(proof)
+        self.quorum = quorum;
+        Ok(())
+    }
+
+    // Sends the JSON-RPC request to the endpoints in order and parses their responses,
+    // given the id of the request, until `quorum` endpoints return the same result
+    fn query<T: PartialEq>(
+        &self,
+        method: &str,
+        params: &str,
+        parse: impl Fn(&[u8], u32) -> Result<T>,
+    ) -> Result<T> {
+        let mut results: Vec<T> = Vec::new();
+        let mut last_error = Error::RpcRequestFailed;
+
+        for endpoint in self.endpoints.iter() {
+            let id = utils::rpc::request_id();
+            let data = format!(
+                r#"{{"id":{},"jsonrpc":"2.0","method":"{}","params":{}}}"#,
+                id, method, params
+            )
+            .into_bytes();
+
+            let result =
+                utils::rpc::call_rpc(endpoint, data).and_then(|resp_body| parse(&resp_body, id));
+            let value = match result {
+                Ok(value) => value,
+                Err(err) => {
+                    // A transport error of a later endpoint must not hide what a node reported
+                    if informativeness(&err) >= informativeness(&last_error) {
+                        last_error = err;
+                    }
+                    continue;
+                }
+            };
+
+            // The endpoints that previously returned the same result, along with this one
+            let votes = results.iter().filter(|result| **result == value).count() + 1;
+            if votes >= self.quorum as usize {
+                return Ok(value);
+            }
+            results.push(value);
+        }
+
+        if results.is_empty() {
+            Err(last_error)
+        } else {
+            Err(Error::RpcQuorumNotReached)
+        }
+    }
+
+    /// Retrieves a single read proof of all the keys at the block and verifies it with `verify`.
+    ///
+    /// The quorum applies to the verified values, since endpoints may return different proofs of the same values.
+    pub fn get_read_proof_multi<V: PartialEq>(
+        &self,
+        secure_storage_keys: &[Vec<u8>],
+        at: &[u8],
+        verify: impl Fn(Vec<Vec<u8>>) -> Result<V>,
+    ) -> Result<V> {
+        let storage_keys: Vec<String> = secure_storage_keys
+            .iter()
+            .map(|key| format!(r#""0x{}""#, utils::rpc::encode_to_hex(key)))
+            .collect();
+        let at_hex = format!("0x{}", utils::rpc::encode_to_hex(at));
+        let params = format!(r#"[[{}], "{}"]"#, storage_keys.join(","), at_hex);
+
+        self.query("state_getReadProof", &params, |resp_body, id| {
+            let read_proof: ReadProofAtBlock = utils::rpc::parse_result(resp_body, id)?;
+            if utils::rpc::extract_hex_from(read_proof.at)? != at {
+                return Err(Error::ReadProofBlockMismatch);
+            }
+
+            // construct the proof
+            let mut proof = Vec::new();
+            for hex_str in read_proof.proof.into_iter() {
+                let trie_node_hash = utils::rpc::extract_hex_from(hex_str)?;
+                proof.push(trie_node_hash);
+            }
+
+            verify(proof)
+        })
+    }
+
+    pub fn get_block_hash(&self, height: u32) -> Result<Vec<u8>> {
+        let params = format!("[{}]", height);
+
+        self.query("chain_getBlockHash", &params, |resp_body, id| {
+            let block_hash: &str = utils::rpc::parse_result(resp_body, id)?;
+
+            utils::rpc::extract_hex_from(block_hash)
+        })
+    }
+
+    /// Retrieves the state version of the runtime at the block, if the runtime declares it
+    pub fn get_state_version(&self, at: &[u8]) -> Result<Option<StateVersion>> {
+        let at = format!("0x{}", utils::rpc::encode_to_hex(at));
+
+        let params = format!(r#"["{}"]"#, at);
+
+        self.query("state_getRuntimeVersion", &params, |resp_body, id| {
+            let runtime_version: RuntimeVersion = utils::rpc::parse_result(resp_body, id)?;
+
+            runtime_version
+                .state_version
+                .map(StateVersion::try_from)
+                .transpose()
+        })
+    }
+
+    /// Retrieves the encoded GRANDPA finality proof of the block at `height`, if any
+    pub fn prove_finality(&self, height: u32) -> Result<Option<Vec<u8>>> {
+        let params = format!("[{}]", height);
+
+        self.query("grandpa_proveFinality", &params, |resp_body, id| {
+            let finality_proof: Option<&str> = utils::rpc::parse_optional_result(resp_body, id)?;
+
+            finality_proof.map(utils::rpc::extract_hex_from).transpose()
+        })
+    }
+
+    pub fn get_header(&self, at: &[u8]) -> Result<Header> {
+        let at = format!("0x{}", utils::rpc::encode_to_hex(at));
+
+        let params = format!(r#"["{}"]"#, at);
+
+        self.query("chain_getHeader", &params, |resp_body, id| {
+            let header: RpcHeader = utils::rpc::parse_result(resp_body, id)?;
+
+            // construct the SCALE header from the JSON one, the digest logs are already SCALE encoded
+            let mut encoded = HeaderFields {
+                parent_hash: h256_from_hex(header.parent_hash)?,
+                number: utils::rpc::extract_number_from(header.number)?,
+                state_root: h256_from_hex(header.state_root)?,
+                extrinsics_root: h256_from_hex(header.extrinsics_root)?,
+            }
+            .encode();
+            Compact(header.digest.logs.len() as u32).encode_to(&mut encoded);
+            for hex_str in header.digest.logs.into_iter() {
+                encoded.extend(utils::rpc::extract_hex_from(hex_str)?);
+            }
+
+            Ok(Header::new(encoded))
+        })
+    }
+}
+
+fn h256_from_hex(hex_string: &str) -> Result<H256> {
+    let bytes = utils::rpc::extract_hex_from(hex_string)?;
+    if bytes.len() != 32 {
+        return Err(Error::InvalidHashBytes);
+    }
+
+    Ok(H256::from_slice(&bytes))
+}
+
+// How much an error tells about the failure of a request: the errors reported by nodes tell more
+// than transport or decoding errors, and the classified ones more than the others
+fn informativeness(error: &Error) -> u8 {
+    match error {
+        Error::StateDiscarded(_) | Error::UnknownBlock(_) | Error::RpcMethodNotFound(_) => 2,
+        Error::RpcNodeError(_) => 1,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::rpc::RpcErrorInfo;
+
+    #[test]
+    fn node_errors_are_more_informative_than_transport_errors() {
+        let info = || RpcErrorInfo {
+            code: 4003,
+            message: String::from("State already discarded"),
+            data: None,
+        };
+
+        assert!(
+            informativeness(&Error::StateDiscarded(info()))
+                > informativeness(&Error::RpcNodeError(info()))
+        );
+        assert!(
+            informativeness(&Error::UnknownBlock(info()))
+                > informativeness(&Error::RpcNodeError(info()))
+        );
+        assert!(
+            informativeness(&Error::RpcNodeError(info()))
+                > informativeness(&Error::RpcRequestFailed)
+        );
+        assert!(
+            informativeness(&Error::RpcNodeError(info())) > informativeness(&Error::RpcInvalidBody)
+        );
     }
 }
@@ -0,0 +1,151 @@
+use super::{rpc::Rpc, verifier};
+use crate::types::{Result, SnapshotCommitment};
+use alloc::vec::Vec;
+
+/// A source of storage read proofs of a block.
+///
+/// Proofs are always verified against the committed state root, so a source only has to provide
+/// the trie nodes: the JSON-RPC of a node, a relayer, or a light client sync service.
+/// The raw values are returned in the order of the keys, and callers decode them.
+pub trait ProofSource {
+    /// Reads the values of the keys from a read proof of the snapshot block verified against the snapshot state root
+    fn read_values(
+        &self,
+        snapshot: &SnapshotCommitment,
+        keys: &[Vec<u8>],
+    ) -> Result<Vec<Option<Vec<u8>>>>;
+}
+
+impl ProofSource for Rpc {
+    fn read_values(
+        &self,
+        snapshot: &SnapshotCommitment,
+        keys: &[Vec<u8>],
+    ) -> Result<Vec<Option<Vec<u8>>>> {
+        self.get_read_proof_multi(keys, &snapshot.block_hash, |storage_proof| {
+            verifier::verify_snapshot_proof(snapshot, keys, storage_proof)
+        })
+    }
+}
+
+/// The trie nodes of a read proof collected off-chain and submitted with the request
+#[derive(Debug, Clone)]
+pub struct SuppliedProof {
+    storage_proof: Vec<Vec<u8>>,
+}
+
+impl SuppliedProof {
+    pub fn new(storage_proof: Vec<Vec<u8>>) -> Self {
+        Self { storage_proof }
+    }
+}
+
+impl ProofSource for SuppliedProof {
+    // The proof is not bound to the block, since it is only valid against its state root
+    fn read_values(
+        &self,
+        snapshot: &SnapshotCommitment,
+        keys: &[Vec<u8>],
+    ) -> Result<Vec<Option<Vec<u8>>>> {
+        verifier::verify_snapshot_proof(snapshot, keys, self.storage_proof.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{crypto::hasher::ContractBlakeTwo256, Error, HashAlgorithm, StateVersion};
+    use alloc::vec;
+    use sp_core::H256;
+    use sp_trie::{
+        recorder::Recorder, LayoutV1, MemoryDB, Trie, TrieDBBuilder, TrieDBMutBuilder, TrieMut,
+    };
+
+    type Layout = LayoutV1<ContractBlakeTwo256>;
+
+    // Values large enough that their trie nodes are not inlined in the parent nodes
+    const ENTRIES: [(&[u8], &[u8; 40]); 3] = [
+        (b"alice", &[1u8; 40]),
+        (b"bob", &[2u8; 40]),
+        (b"charlie", &[3u8; 40]),
+    ];
+
+    // The state of a block with the entries
+    fn state() -> (MemoryDB<ContractBlakeTwo256>, H256) {
+        let mut db = MemoryDB::default();
+        let mut root = H256::zero();
+        {
+            let mut trie = TrieDBMutBuilder::<Layout>::new(&mut db, &mut root).build();
+            for (key, value) in ENTRIES {
+                trie.insert(key, &value[..]).unwrap();
+            }
+        }
+        (db, root)
+    }
+
+    // Records the trie nodes read to look up the keys, as `state_getReadProof` does
+    fn read_proof(db: &MemoryDB<ContractBlakeTwo256>, root: &H256, keys: &[&[u8]]) -> Vec<Vec<u8>> {
+        let recorder = Recorder::<ContractBlakeTwo256>::default();
+        {
+            let mut trie_recorder = recorder.as_trie_recorder(*root);
+            let trie = TrieDBBuilder::<Layout>::new(db, root)
+                .with_recorder(&mut trie_recorder)
+                .build();
+            for key in keys {
+                trie.get(key).unwrap();
+            }
+        }
+        recorder.drain_storage_proof().into_iter_nodes().collect()
+    }
+
+    fn snapshot(root: &H256) -> SnapshotCommitment {
+        SnapshotCommitment {
+            height: 1,
+            block_hash: vec![1u8; 32],
+            state_root: root.as_bytes().to_vec(),
+            hasher: HashAlgorithm::Blake2,
+            state_version: StateVersion::V1,
+            relay_anchor: None,
+        }
+    }
+
+    #[test]
+    fn reads_the_values_of_a_supplied_proof() {
+        let (db, root) = state();
+        let keys: Vec<Vec<u8>> = vec![b"alice".to_vec(), b"dave".to_vec(), b"bob".to_vec()];
+        let proof = read_proof(&db, &root, &[b"alice", b"dave", b"bob"]);
+        let source: &dyn ProofSource = &SuppliedProof::new(proof);
+
+        assert_eq!(
+            verifier::read_verified_values(&snapshot(&root), &keys, source),
+            Ok(vec![Some(vec![1u8; 40]), None, Some(vec![2u8; 40])])
+        );
+    }
+
+    #[test]
+    fn rejects_proofs_of_another_state() {
+        let (db, root) = state();
+        let proof = read_proof(&db, &root, &[b"alice"]);
+
+        let other_root = H256::repeat_byte(1);
+        assert!(matches!(
+            verifier::read_verified_value(
+                &snapshot(&other_root),
+                b"alice",
+                &SuppliedProof::new(proof)
+            ),
+            Err(Error::KeyError(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_keys_missing_from_the_proof() {
+        let (db, root) = state();
+        let proof = read_proof(&db, &root, &[b"alice"]);
+
+        assert!(matches!(
+            verifier::read_verified_value(&snapshot(&root), b"charlie", &SuppliedProof::new(proof)),
+            Err(Error::KeyError(_))
+        ));
+    }
+}
@@ -1,49 +1,109 @@
 use crate::types::crypto::hasher::{ContractBlakeTwo256, ContractKeccak256};
-use crate::types::{HashAlgorithm, SubstrateStateProof};
+use crate::types::{HashAlgorithm, SnapshotCommitment, StateVersion, SubstrateStateProof};
 
+use super::source::ProofSource;
 use crate::types::{Error, Result};
 use alloc::{format, vec::Vec};
-use sp_core::H256;
-use sp_trie::{LayoutV0, StorageProof, Trie, TrieDBBuilder};
+use sp_core::{Hasher, H256};
+use sp_trie::{LayoutV0, LayoutV1, StorageProof, Trie, TrieDBBuilder, TrieLayout};
 
-pub fn verify_state_proof(
-    root: &[u8],
+/// Reads the value at `key` from a read proof provided by the source and verified against the snapshot state root
+pub fn read_verified_value(
+    snapshot: &SnapshotCommitment,
     key: &[u8],
-    state_proof: SubstrateStateProof,
+    source: &dyn ProofSource,
 ) -> Result<Option<Vec<u8>>> {
+    let mut values = read_verified_values(snapshot, &[key.to_vec()], source)?;
+
+    Ok(values.remove(0))
+}
+
+/// Reads the values at `keys` from a single read proof provided by the source and verified against the snapshot state root
+pub fn read_verified_values(
+    snapshot: &SnapshotCommitment,
+    keys: &[Vec<u8>],
+    source: &dyn ProofSource,
+) -> Result<Vec<Option<Vec<u8>>>> {
+    let values = source.read_values(snapshot, keys)?;
+    // Sources are not trusted to return a value for each key
+    if values.len() != keys.len() {
+        return Err(Error::KeyError(format!(
+            "Expected {} values from the proof source, got {}",
+            keys.len(),
+            values.len()
+        )));
+    }
+
+    Ok(values)
+}
+
+/// Verifies the trie nodes against the snapshot state root and reads the value of each key, in order
+pub fn verify_snapshot_proof(
+    snapshot: &SnapshotCommitment,
+    keys: &[Vec<u8>],
+    storage_proof: Vec<Vec<u8>>,
+) -> Result<Vec<Option<Vec<u8>>>> {
+    let proof = SubstrateStateProof {
+        hasher: snapshot.hasher.clone(),
+        state_version: snapshot.state_version,
+        storage_proof,
+    };
+
+    verify_state_proof_multi(&snapshot.state_root, keys, proof)
+}
+
+/// Verifies the state proof and reads the value of each key, in order
+pub fn verify_state_proof_multi(
+    root: &[u8],
+    keys: &[Vec<u8>],
+    state_proof: SubstrateStateProof,
+) -> Result<Vec<Option<Vec<u8>>>> {
     let root = h256_from_slice(root)?;
 
-    let data = match state_proof.hasher {
-        HashAlgorithm::Keccak => {
-            let db =
-                StorageProof::new(state_proof.storage_proof).into_memory_db::<ContractKeccak256>();
-            let trie = TrieDBBuilder::<LayoutV0<ContractKeccak256>>::new(&db, &root).build();
+    let proof = StorageProof::new(state_proof.storage_proof);
 
-            let value = trie
-                .get(key)
-                .map_err(|e| Error::KeyError(format!("Error reading state proof: {e:?}")))?;
-            value
+    match (state_proof.hasher, state_proof.state_version) {
+        (HashAlgorithm::Keccak, StateVersion::V0) => {
+            read_values::<LayoutV0<ContractKeccak256>>(proof, &root, keys)
         }
-        HashAlgorithm::Blake2 => {
-            let db = StorageProof::new(state_proof.storage_proof)
-                .into_memory_db::<ContractBlakeTwo256>();
-            let trie = TrieDBBuilder::<LayoutV0<ContractBlakeTwo256>>::new(&db, &root).build();
-
-            let value = trie
-                .get(key)
-                .map_err(|e| Error::KeyError(format!("Error reading state proof: {e:?}")))?;
-            value
+        (HashAlgorithm::Keccak, StateVersion::V1) => {
+            read_values::<LayoutV1<ContractKeccak256>>(proof, &root, keys)
         }
-    };
+        (HashAlgorithm::Blake2, StateVersion::V0) => {
+            read_values::<LayoutV0<ContractBlakeTwo256>>(proof, &root, keys)
+        }
+        (HashAlgorithm::Blake2, StateVersion::V1) => {
+            read_values::<LayoutV1<ContractBlakeTwo256>>(proof, &root, keys)
+        }
+    }
+}
+
+// Reads the values of the keys from the trie with layout `L`
+fn read_values<L>(
+    proof: StorageProof,
+    root: &H256,
+    keys: &[Vec<u8>],
+) -> Result<Vec<Option<Vec<u8>>>>
+where
+    L: TrieLayout,
+    L::Hash: Hasher<Out = H256>,
+{
+    let db = proof.into_memory_db::<L::Hash>();
+    let trie = TrieDBBuilder::<L>::new(&db, root).build();
 
-    Ok(data)
+    keys.iter()
+        .map(|key| {
+            trie.get(key)
+                .map_err(|e| Error::KeyError(format!("Error reading state proof: {e:?}")))
+        })
+        .collect()
 }
 
-fn h256_from_slice(maybe_h256: &[u8]) -> Result<H256> {
+pub fn h256_from_slice(maybe_h256: &[u8]) -> Result<H256> {
     // Ensure the vector has exactly 32 bytes
     if maybe_h256.len() == 32 {
         let mut x = [0u8; 32];
-        x.copy_from_slice(&maybe_h256);
+        x.copy_from_slice(maybe_h256);
         Ok(H256::from(x))
     } else {
         Err(Error::InvalidHashBytes)
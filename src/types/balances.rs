@@ -1,38 +1,603 @@
-use super::evm::{Address, EncodedMessage, SignedMessage};
+use super::{
+    crypto::hasher::ContractKeccak256,
+    eip712::{hash_struct, Eip712Struct},
+    evm::{Address, EncodedMessage, SignatureScheme, SignedMessage},
+    ChainId, Error, Result,
+};
+use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
 use ethabi::{encode as abi_encode, Token};
 use ink::primitives::AccountId;
 use scale::{Decode, Encode};
+use sp_core::{Hasher, H256};
 
 pub type Balance = u128;
 
+/// The EIP-712 type of a signed balance request.
+///
+/// The EVM chain id and verifying contract are part of the EIP-712 domain.
+const BALANCE_PROOF_TYPE: &str = "BalanceProof(bytes32 genesisHash,bytes32 substrateAccount,address claimAddress,uint32 assetId,uint8 assetDecimals,uint8 bucketing,uint256 bucketingParameter,uint256 amount,uint8 balanceKind,uint64 nonce,uint64 expiresAt)";
+
+/// The EIP-712 type of a signed balance threshold request.
+///
+/// The EVM chain id and verifying contract are part of the EIP-712 domain.
+const BALANCE_THRESHOLD_PROOF_TYPE: &str = "BalanceThresholdProof(bytes32 genesisHash,bytes32 substrateAccount,address claimAddress,uint32 assetId,uint8 assetDecimals,uint8 balanceKind,uint256 threshold,bool satisfied,uint64 nonce,uint64 expiresAt)";
+
+/// The EIP-712 type of a signed balance commitment request.
+///
+/// The EVM chain id and verifying contract are part of the EIP-712 domain.
+const BALANCE_COMMITMENT_PROOF_TYPE: &str = "BalanceCommitmentProof(bytes32 genesisHash,bytes32 substrateAccount,address claimAddress,uint32 assetId,uint8 assetDecimals,uint8 balanceKind,bytes32 commitment,uint64 nonce,uint64 expiresAt)";
+
 #[derive(Debug, Encode, Decode, Clone, scale_info::TypeInfo)]
 #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
 pub struct Asset {
-    id: u32,
+    id: AssetId,
     decimals: u8,
+    /// The rounding of the signed amounts, so that exact balances are not published
+    bucketing: BucketingPolicy,
+}
+
+impl Asset {
+    pub fn id(&self) -> AssetId {
+        self.id
+    }
+
+    pub fn bucketing(&self) -> BucketingPolicy {
+        self.bucketing
+    }
+
+    /// Ensures the asset can be registered
+    pub fn validate(&self) -> Result<()> {
+        self.bucketing.validate()
+    }
+}
+
+/// How a balance is rounded down before it is signed
+#[derive(Debug, Encode, Decode, Clone, Copy, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub enum BucketingPolicy {
+    /// The exact balance is signed
+    Exact,
+    /// Floors the balance to the given number of significant (decimal) digits
+    SignificantDigits(u8),
+    /// Floors the balance to a power of the given base
+    Logarithmic(u32),
+    /// Floors the balance to a multiple of the given step
+    FixedStep(Balance),
+}
+
+impl BucketingPolicy {
+    /// The identifier of the policy within the signed request
+    pub fn id(&self) -> u8 {
+        match self {
+            BucketingPolicy::Exact => 0,
+            BucketingPolicy::SignificantDigits(_) => 1,
+            BucketingPolicy::Logarithmic(_) => 2,
+            BucketingPolicy::FixedStep(_) => 3,
+        }
+    }
+
+    /// The parameter of the policy within the signed request
+    pub fn parameter(&self) -> Balance {
+        match self {
+            BucketingPolicy::Exact => 0,
+            BucketingPolicy::SignificantDigits(digits) => (*digits).into(),
+            BucketingPolicy::Logarithmic(base) => (*base).into(),
+            BucketingPolicy::FixedStep(step) => *step,
+        }
+    }
+
+    /// Ensures the parameter of the policy defines buckets,
+    /// i.e. at least one significant digit, a base of at least 2 and a non zero step
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            BucketingPolicy::SignificantDigits(0)
+            | BucketingPolicy::Logarithmic(0..=1)
+            | BucketingPolicy::FixedStep(0) => Err(Error::InvalidBucketingPolicy),
+            _ => Ok(()),
+        }
+    }
+
+    /// Rounds down the balance to its bucket.
+    ///
+    /// The policy is expected to be validated, the balance of an invalid one is rounded down to 0 rather than panicking.
+    pub fn apply(&self, amount: Balance) -> Balance {
+        match *self {
+            BucketingPolicy::Exact => amount,
+            BucketingPolicy::SignificantDigits(digits) => {
+                let total_digits = amount.checked_ilog10().map_or(0, |log| log + 1);
+                match total_digits.checked_sub(digits.into()) {
+                    // Steps beyond the range of balances round every balance down to 0
+                    Some(exp) => match 10u128.checked_pow(exp) {
+                        Some(step) => amount - amount % step,
+                        None => 0,
+                    },
+                    // The balance has no more digits than the significant ones
+                    None => amount,
+                }
+            }
+            BucketingPolicy::Logarithmic(base) => {
+                let base = Balance::from(base);
+                match amount.checked_ilog(base) {
+                    Some(exp) => base.pow(exp),
+                    None => 0,
+                }
+            }
+            BucketingPolicy::FixedStep(step) => amount
+                .checked_rem(step)
+                .map_or(0, |remainder| amount - remainder),
+        }
+    }
+}
+
+/// The id of an asset
+pub type AssetId = u32;
+
+/// The key of a registered asset, since asset ids are only unique within a chain
+pub type AssetKey = (ChainId, AssetId);
+
+/// An asset registered in the prover, along with where its balances are stored
+#[derive(Debug, Encode, Decode, Clone, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct AssetEntry {
+    /// The chain that holds the balances of the asset
+    pub chain_id: ChainId,
+    pub asset: Asset,
+    /// The layout of the balances storage key
+    pub storage_key: StorageKeyLayout,
+    /// The layout of the balances storage value
+    pub value_layout: BalanceValueLayout,
+    /// The balance to attest
+    pub kind: BalanceKind,
+    /// Whether the balances of the asset can be proven
+    pub enabled: bool,
+}
+
+impl AssetEntry {
+    pub fn key(&self) -> AssetKey {
+        (self.chain_id, self.asset.id)
+    }
+}
+
+/// The hasher of a storage map key, i.e. `frame_support::StorageHasher`
+#[derive(Debug, Encode, Decode, Clone, Copy, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub enum KeyHasher {
+    Blake2_128,
+    Blake2_256,
+    Blake2_128Concat,
+    Twox128,
+    Twox256,
+    Twox64Concat,
+    Identity,
+}
+
+/// A key of the storage map that holds the balances
+#[derive(Debug, Encode, Decode, Clone, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub enum StorageKeyComponent {
+    /// The id of the asset
+    AssetId(KeyHasher),
+    /// The account that holds the balance
+    Account(KeyHasher),
+    /// A key that is already SCALE encoded (e.g. an ORML `CurrencyId`)
+    Encoded(KeyHasher, Vec<u8>),
+}
+
+/// The layout of the storage key that holds the balance
+#[derive(Debug, Encode, Decode, Clone, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub enum StorageKeyLayout {
+    /// A precomputed storage key prefix, to which the `Blake2_128Concat` account key is appended
+    Prefix(Vec<u8>),
+    /// `twox128(pallet) ++ twox128(item)`, followed by the hashed keys of the storage map in order
+    Map {
+        pallet: String,
+        item: String,
+        keys: Vec<StorageKeyComponent>,
+    },
+}
+
+impl StorageKeyLayout {
+    /// The layout of a `pallet_assets` accounts double map, keyed by the asset id and then by the account
+    pub fn pallet_assets(pallet: String, item: String) -> Self {
+        StorageKeyLayout::Map {
+            pallet,
+            item,
+            keys: vec![
+                StorageKeyComponent::AssetId(KeyHasher::Blake2_128Concat),
+                StorageKeyComponent::Account(KeyHasher::Blake2_128Concat),
+            ],
+        }
+    }
+
+    /// The layout of the `orml_tokens::Accounts` double map, keyed by the account and then by the encoded currency id
+    pub fn orml_tokens(currency_id: Vec<u8>) -> Self {
+        StorageKeyLayout::Map {
+            pallet: String::from("Tokens"),
+            item: String::from("Accounts"),
+            keys: vec![
+                StorageKeyComponent::Account(KeyHasher::Blake2_128Concat),
+                StorageKeyComponent::Encoded(KeyHasher::Twox64Concat, currency_id),
+            ],
+        }
+    }
+}
+
+/// The layout of the SCALE encoded storage value that holds the balance
+#[derive(Debug, Encode, Decode, Clone, Copy, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub enum BalanceValueLayout {
+    /// The value starts with the balance (e.g. `pallet_assets::Account` balance)
+    Balance,
+    /// `frame_system::AccountInfo` with the current `pallet_balances::AccountData`
+    AccountInfo,
+    /// `frame_system::AccountInfo` with the legacy `AccountData` (`misc_frozen` and `fee_frozen`)
+    LegacyAccountInfo,
+    /// `pallet_assets::AssetAccount`
+    AssetAccount,
+    /// `orml_tokens::AccountData`
+    OrmlAccountData,
+}
+
+/// The balance to attest, its index is the `balanceKind` of the signed request
+#[derive(Debug, Encode, Decode, Clone, Copy, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub enum BalanceKind {
+    Free = 0,
+    Reserved = 1,
+    /// For the legacy `AccountData` this is the max between `misc_frozen` and `fee_frozen`
+    Frozen = 2,
+    /// The free balance that can be transferred
+    Transferable = 3,
+    /// The free and reserved balance
+    Total = 4,
+}
+
+/// The balances of an account, whatever the layout of the storage value
+pub struct AccountBalances {
+    pub free: Balance,
+    pub reserved: Balance,
+    pub frozen: Balance,
+    pub transferable: Balance,
+}
+
+impl AccountBalances {
+    /// The balances of an account that only holds a free balance
+    pub fn from_balance(balance: Balance) -> Self {
+        Self {
+            free: balance,
+            reserved: 0,
+            frozen: 0,
+            transferable: balance,
+        }
+    }
+}
+
+impl BalanceKind {
+    /// Selects the balance to attest
+    pub fn select(&self, balances: &AccountBalances) -> Balance {
+        match self {
+            BalanceKind::Free => balances.free,
+            BalanceKind::Reserved => balances.reserved,
+            BalanceKind::Frozen => balances.frozen,
+            BalanceKind::Transferable => balances.transferable,
+            BalanceKind::Total => balances.free.saturating_add(balances.reserved),
+        }
+    }
+}
+
+/// `frame_system::AccountInfo`, the fields preceding the account data are only decoded to skip them
+#[derive(Decode)]
+#[allow(dead_code)]
+pub struct AccountInfo<Data> {
+    pub nonce: u32,
+    pub consumers: u32,
+    pub providers: u32,
+    pub sufficients: u32,
+    pub data: Data,
+}
+
+/// `pallet_balances::AccountData`
+#[derive(Decode)]
+pub struct AccountData {
+    pub free: Balance,
+    pub reserved: Balance,
+    pub frozen: Balance,
+    pub flags: u128,
+}
+
+impl From<AccountData> for AccountBalances {
+    fn from(data: AccountData) -> Self {
+        // The frozen balance applies to the free and reserved balances together
+        let untouchable = data.frozen.saturating_sub(data.reserved);
+
+        Self {
+            free: data.free,
+            reserved: data.reserved,
+            frozen: data.frozen,
+            transferable: data.free.saturating_sub(untouchable),
+        }
+    }
+}
+
+/// `pallet_balances::AccountData` before the fungible traits migration
+#[derive(Decode)]
+pub struct LegacyAccountData {
+    pub free: Balance,
+    pub reserved: Balance,
+    pub misc_frozen: Balance,
+    pub fee_frozen: Balance,
+}
+
+impl From<LegacyAccountData> for AccountBalances {
+    fn from(data: LegacyAccountData) -> Self {
+        // The frozen balance only applies to the free balance
+        let frozen = data.misc_frozen.max(data.fee_frozen);
+
+        Self {
+            free: data.free,
+            reserved: data.reserved,
+            frozen,
+            transferable: data.free.saturating_sub(frozen),
+        }
+    }
+}
+
+/// `orml_tokens::AccountData`
+#[derive(Decode)]
+pub struct OrmlAccountData {
+    pub free: Balance,
+    pub reserved: Balance,
+    pub frozen: Balance,
+}
+
+impl From<OrmlAccountData> for AccountBalances {
+    fn from(data: OrmlAccountData) -> Self {
+        // The frozen balance only applies to the free balance
+        Self {
+            free: data.free,
+            reserved: data.reserved,
+            frozen: data.frozen,
+            transferable: data.free.saturating_sub(data.frozen),
+        }
+    }
+}
+
+/// `pallet_assets::AssetAccount`, the fields following the balance are not decoded
+#[derive(Decode)]
+pub struct AssetAccount {
+    pub balance: Balance,
+}
+
+impl BalanceValueLayout {
+    /// Decodes the storage value and returns the balance of the given kind
+    pub fn decode_balance(&self, value: &[u8], kind: BalanceKind) -> Result<Balance> {
+        let input = &mut &*value;
+        let balances: AccountBalances = match self {
+            BalanceValueLayout::Balance => {
+                Balance::decode(input).map(AccountBalances::from_balance)
+            }
+            BalanceValueLayout::AccountInfo => {
+                AccountInfo::<AccountData>::decode(input).map(|info| info.data.into())
+            }
+            BalanceValueLayout::LegacyAccountInfo => {
+                AccountInfo::<LegacyAccountData>::decode(input).map(|info| info.data.into())
+            }
+            BalanceValueLayout::AssetAccount => AssetAccount::decode(input)
+                .map(|account| AccountBalances::from_balance(account.balance)),
+            BalanceValueLayout::OrmlAccountData => OrmlAccountData::decode(input).map(Into::into),
+        }
+        .map_err(|_| Error::InvalidBalanceDecoding)?;
+
+        Ok(kind.select(&balances))
+    }
+}
+
+/// The EVM chain and contract that the signed requests are meant for
+#[derive(Debug, Encode, Decode, Clone, Copy, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct EvmTarget {
+    pub chain_id: u64,
+    pub verifying_contract: Address,
+}
+
+/// The type of a signed request, tagged in its ABI encoding so that requests of different types cannot be mistaken
+/// for one another, and bound to its nonce so that each type of request has its own nonces
+#[derive(Debug, Encode, Decode, Clone, Copy, PartialEq, scale_info::TypeInfo)]
+pub enum RequestType {
+    /// A `ProverRequest`
+    Balance,
+    /// A `ThresholdRequest`
+    Threshold,
+    /// A `CommitmentRequest`
+    Commitment,
+}
+
+impl RequestType {
+    /// The leading tag of the ABI encoding of the request, i.e. the `keccak256` hash of the request name
+    pub fn tag(&self) -> H256 {
+        let name: &[u8] = match self {
+            RequestType::Balance => b"ProverRequest",
+            RequestType::Threshold => b"ThresholdRequest",
+            RequestType::Commitment => b"CommitmentRequest",
+        };
+
+        ContractKeccak256::hash(name)
+    }
+}
+
+/// The data that prevents a signed request from being replayed.
+///
+/// Requests are signed within queries, whose state changes are discarded, hence the prover does not track them:
+/// replay protection is enforced by the EVM verifier, which must consume the nonce of every request it accepts
+/// and reject the requests whose nonce it has already consumed for the account.
+/// The nonce is the same for every signature of a request at a snapshot, so consuming it on the EVM side
+/// replaces refusing to sign a request twice, which the prover cannot record.
+#[derive(Debug, Clone, Copy)]
+pub struct ReplayProtection {
+    /// The nonce of the request, derived from its type, the snapshot block, the account and the asset
+    pub nonce: u64,
+    /// The timestamp (in seconds) after which the signed request is no longer valid
+    pub expires_at: u64,
+    /// The EVM chain and contract the request is meant for
+    pub target: EvmTarget,
+}
+
+impl ReplayProtection {
+    pub fn new(
+        request_type: RequestType,
+        snapshot: &[u8],
+        substrate_account: &AccountId,
+        asset: AssetKey,
+        expires_at: u64,
+        target: EvmTarget,
+    ) -> Self {
+        Self {
+            nonce: request_nonce(request_type, snapshot, substrate_account, asset),
+            expires_at,
+            target,
+        }
+    }
+}
+
+/// The nonce of the requests of a type of an account for an asset at the snapshot block,
+/// i.e. the first 8 bytes of `keccak256(SCALE(request_type, snapshot, account, asset))`.
+///
+/// Since the nonce is the same for every request of the type at the snapshot, a balance can be claimed once per snapshot
+/// with each type of request.
+pub fn request_nonce(
+    request_type: RequestType,
+    snapshot: &[u8],
+    substrate_account: &AccountId,
+    asset: AssetKey,
+) -> u64 {
+    let hash =
+        ContractKeccak256::hash(&(request_type, snapshot, substrate_account, asset).encode());
+
+    let mut nonce = [0u8; 8];
+    nonce.copy_from_slice(&hash.as_bytes()[..8]);
+    u64::from_be_bytes(nonce)
+}
+
+/// The payload a Substrate account signs to let anyone prove its balance of an asset on its behalf.
+///
+/// The signed request is single use, since its nonce is derived from the snapshot, the account and the asset.
+#[derive(Debug, Encode)]
+pub struct DelegatedProofPayload {
+    /// The domain of the payload, i.e. `DELEGATED_PROOF_DOMAIN`
+    domain: Vec<u8>,
+    /// The account of the prover contract
+    prover: AccountId,
+    substrate_account: AccountId,
+    evm_address: Address,
+    /// The genesis hash of the chain that holds the balance
+    genesis_hash: Vec<u8>,
+    asset_id: AssetId,
+    /// The block hash of the snapshot
+    snapshot: Vec<u8>,
+    /// The timestamp (in seconds) after which the payload can no longer be used
+    expires_at: u64,
+}
+
+/// The domain of the delegated proof payload
+pub const DELEGATED_PROOF_DOMAIN: &[u8] = b"balances-prover:prove_balance_for";
+
+impl DelegatedProofPayload {
+    pub fn new(
+        prover: AccountId,
+        substrate_account: AccountId,
+        evm_address: Address,
+        genesis_hash: Vec<u8>,
+        asset_id: AssetId,
+        snapshot: Vec<u8>,
+        expires_at: u64,
+    ) -> Self {
+        Self {
+            domain: DELEGATED_PROOF_DOMAIN.to_vec(),
+            prover,
+            substrate_account,
+            evm_address,
+            genesis_hash,
+            asset_id,
+            snapshot,
+            expires_at,
+        }
+    }
+}
+
+/// The payload the owner of an EVM address signs, with EIP-191 `personal_sign`, to consent to claim the balance
+/// of a Substrate account for an asset
+#[derive(Debug, Encode)]
+pub struct ClaimConsentPayload {
+    /// The domain of the payload, i.e. `CLAIM_CONSENT_DOMAIN`
+    domain: Vec<u8>,
+    /// The account of the prover contract
+    prover: AccountId,
+    substrate_account: AccountId,
+    /// The genesis hash of the chain that holds the balance
+    genesis_hash: Vec<u8>,
+    asset_id: AssetId,
+    /// The block hash of the snapshot
+    snapshot: Vec<u8>,
+    /// The timestamp (in seconds) after which the payload can no longer be used
+    expires_at: u64,
+}
+
+/// The domain of the claim consent payload
+pub const CLAIM_CONSENT_DOMAIN: &[u8] = b"balances-prover:claim_consent";
+
+impl ClaimConsentPayload {
+    pub fn new(
+        prover: AccountId,
+        substrate_account: AccountId,
+        genesis_hash: Vec<u8>,
+        asset_id: AssetId,
+        snapshot: Vec<u8>,
+        expires_at: u64,
+    ) -> Self {
+        Self {
+            domain: CLAIM_CONSENT_DOMAIN.to_vec(),
+            prover,
+            substrate_account,
+            genesis_hash,
+            asset_id,
+            snapshot,
+            expires_at,
+        }
+    }
 }
 
 pub struct ProverRequest {
+    /// The genesis hash of the chain that holds the balance
+    genesis_hash: Vec<u8>,
     substrate_account: AccountId,
     evm_address: Address,
     asset: Asset,
     amount: Balance,
+    kind: BalanceKind,
+    replay_protection: ReplayProtection,
 }
 
 impl ProverRequest {
     pub fn new(
+        genesis_hash: Vec<u8>,
         substrate_account: AccountId,
         evm_address: Address,
         asset: Asset,
         amount: Balance,
+        kind: BalanceKind,
+        replay_protection: ReplayProtection,
     ) -> Self {
         Self {
+            genesis_hash,
             substrate_account,
             evm_address,
             asset,
             amount,
+            kind,
+            replay_protection,
         }
     }
 }
@@ -42,6 +607,10 @@ impl Encode for ProverRequest {
         let account: &[u8; 32] = self.substrate_account.as_ref();
 
         let tokens = vec![
+            // request type tag
+            Token::FixedBytes(RequestType::Balance.tag().as_bytes().to_vec()),
+            // chain genesis hash
+            Token::FixedBytes(self.genesis_hash.clone()),
             // substrate account
             Token::Bytes(account.to_vec()),
             // address
@@ -52,18 +621,277 @@ impl Encode for ProverRequest {
                 Token::Uint(self.asset.id.into()),
                 // asset decimals
                 Token::Uint(self.asset.decimals.into()),
+                // bucketing policy
+                Token::Uint(self.asset.bucketing.id().into()),
+                // bucketing policy parameter
+                Token::Uint(self.asset.bucketing.parameter().into()),
             ]),
             // balance amount
             Token::Uint(self.amount.into()),
+            // balance kind
+            Token::Uint((self.kind as u8).into()),
+            // nonce
+            Token::Uint(self.replay_protection.nonce.into()),
+            // expiration timestamp
+            Token::Uint(self.replay_protection.expires_at.into()),
+            // EVM chain id
+            Token::Uint(self.replay_protection.target.chain_id.into()),
+            // verifying contract
+            Token::Address(self.replay_protection.target.verifying_contract.into()),
+        ];
+
+        abi_encode(&tokens)
+    }
+}
+
+impl Eip712Struct for ProverRequest {
+    fn struct_hash(&self) -> H256 {
+        let account: &[u8; 32] = self.substrate_account.as_ref();
+
+        hash_struct(
+            BALANCE_PROOF_TYPE,
+            vec![
+                Token::FixedBytes(self.genesis_hash.clone()),
+                Token::FixedBytes(account.to_vec()),
+                Token::Address(self.evm_address.into()),
+                Token::Uint(self.asset.id.into()),
+                Token::Uint(self.asset.decimals.into()),
+                Token::Uint(self.asset.bucketing.id().into()),
+                Token::Uint(self.asset.bucketing.parameter().into()),
+                Token::Uint(self.amount.into()),
+                Token::Uint((self.kind as u8).into()),
+                Token::Uint(self.replay_protection.nonce.into()),
+                Token::Uint(self.replay_protection.expires_at.into()),
+            ],
+        )
+    }
+}
+
+/// A request that attests the balance meets a threshold, without revealing the amount
+pub struct ThresholdRequest {
+    /// The genesis hash of the chain that holds the balance
+    genesis_hash: Vec<u8>,
+    substrate_account: AccountId,
+    evm_address: Address,
+    asset: Asset,
+    kind: BalanceKind,
+    threshold: Balance,
+    replay_protection: ReplayProtection,
+}
+
+impl ThresholdRequest {
+    pub fn new(
+        genesis_hash: Vec<u8>,
+        substrate_account: AccountId,
+        evm_address: Address,
+        asset: Asset,
+        kind: BalanceKind,
+        threshold: Balance,
+        replay_protection: ReplayProtection,
+    ) -> Self {
+        Self {
+            genesis_hash,
+            substrate_account,
+            evm_address,
+            asset,
+            kind,
+            threshold,
+            replay_protection,
+        }
+    }
+}
+
+impl Encode for ThresholdRequest {
+    fn encode(&self) -> Vec<u8> {
+        let account: &[u8; 32] = self.substrate_account.as_ref();
+
+        let tokens = vec![
+            // request type tag
+            Token::FixedBytes(RequestType::Threshold.tag().as_bytes().to_vec()),
+            // chain genesis hash
+            Token::FixedBytes(self.genesis_hash.clone()),
+            // substrate account
+            Token::Bytes(account.to_vec()),
+            // address
+            Token::Address(self.evm_address.into()),
+            // asset
+            Token::Tuple(vec![
+                // asset id
+                Token::Uint(self.asset.id.into()),
+                // asset decimals
+                Token::Uint(self.asset.decimals.into()),
+            ]),
+            // balance kind
+            Token::Uint((self.kind as u8).into()),
+            // balance threshold
+            Token::Uint(self.threshold.into()),
+            // the balance meets the threshold, since the request is signed only in that case
+            Token::Bool(true),
+            // nonce
+            Token::Uint(self.replay_protection.nonce.into()),
+            // expiration timestamp
+            Token::Uint(self.replay_protection.expires_at.into()),
+            // EVM chain id
+            Token::Uint(self.replay_protection.target.chain_id.into()),
+            // verifying contract
+            Token::Address(self.replay_protection.target.verifying_contract.into()),
+        ];
+
+        abi_encode(&tokens)
+    }
+}
+
+impl Eip712Struct for ThresholdRequest {
+    fn struct_hash(&self) -> H256 {
+        let account: &[u8; 32] = self.substrate_account.as_ref();
+
+        hash_struct(
+            BALANCE_THRESHOLD_PROOF_TYPE,
+            vec![
+                Token::FixedBytes(self.genesis_hash.clone()),
+                Token::FixedBytes(account.to_vec()),
+                Token::Address(self.evm_address.into()),
+                Token::Uint(self.asset.id.into()),
+                Token::Uint(self.asset.decimals.into()),
+                Token::Uint((self.kind as u8).into()),
+                Token::Uint(self.threshold.into()),
+                Token::Bool(true),
+                Token::Uint(self.replay_protection.nonce.into()),
+                Token::Uint(self.replay_protection.expires_at.into()),
+            ],
+        )
+    }
+}
+
+/// The commitment to a balance, i.e. `keccak256(abi.encode(substrateAccount, assetId, amount, salt))`
+pub fn balance_commitment(
+    substrate_account: &AccountId,
+    asset: &Asset,
+    amount: Balance,
+    salt: &[u8],
+) -> H256 {
+    let account: &[u8; 32] = substrate_account.as_ref();
+
+    let tokens = vec![
+        Token::FixedBytes(account.to_vec()),
+        Token::Uint(asset.id.into()),
+        Token::Uint(amount.into()),
+        Token::FixedBytes(salt.to_vec()),
+    ];
+
+    ContractKeccak256::hash(&abi_encode(&tokens))
+}
+
+/// A request that attests a commitment to the balance, without revealing the amount
+pub struct CommitmentRequest {
+    /// The genesis hash of the chain that holds the balance
+    genesis_hash: Vec<u8>,
+    substrate_account: AccountId,
+    evm_address: Address,
+    asset: Asset,
+    kind: BalanceKind,
+    commitment: H256,
+    replay_protection: ReplayProtection,
+}
+
+impl CommitmentRequest {
+    pub fn new(
+        genesis_hash: Vec<u8>,
+        substrate_account: AccountId,
+        evm_address: Address,
+        asset: Asset,
+        kind: BalanceKind,
+        commitment: H256,
+        replay_protection: ReplayProtection,
+    ) -> Self {
+        Self {
+            genesis_hash,
+            substrate_account,
+            evm_address,
+            asset,
+            kind,
+            commitment,
+            replay_protection,
+        }
+    }
+}
+
+impl Encode for CommitmentRequest {
+    fn encode(&self) -> Vec<u8> {
+        let account: &[u8; 32] = self.substrate_account.as_ref();
+
+        let tokens = vec![
+            // request type tag
+            Token::FixedBytes(RequestType::Commitment.tag().as_bytes().to_vec()),
+            // chain genesis hash
+            Token::FixedBytes(self.genesis_hash.clone()),
+            // substrate account
+            Token::Bytes(account.to_vec()),
+            // address
+            Token::Address(self.evm_address.into()),
+            // asset
+            Token::Tuple(vec![
+                // asset id
+                Token::Uint(self.asset.id.into()),
+                // asset decimals
+                Token::Uint(self.asset.decimals.into()),
+            ]),
+            // balance kind
+            Token::Uint((self.kind as u8).into()),
+            // balance commitment
+            Token::FixedBytes(self.commitment.as_bytes().to_vec()),
+            // nonce
+            Token::Uint(self.replay_protection.nonce.into()),
+            // expiration timestamp
+            Token::Uint(self.replay_protection.expires_at.into()),
+            // EVM chain id
+            Token::Uint(self.replay_protection.target.chain_id.into()),
+            // verifying contract
+            Token::Address(self.replay_protection.target.verifying_contract.into()),
         ];
 
         abi_encode(&tokens)
     }
 }
 
+impl Eip712Struct for CommitmentRequest {
+    fn struct_hash(&self) -> H256 {
+        let account: &[u8; 32] = self.substrate_account.as_ref();
+
+        hash_struct(
+            BALANCE_COMMITMENT_PROOF_TYPE,
+            vec![
+                Token::FixedBytes(self.genesis_hash.clone()),
+                Token::FixedBytes(account.to_vec()),
+                Token::Address(self.evm_address.into()),
+                Token::Uint(self.asset.id.into()),
+                Token::Uint(self.asset.decimals.into()),
+                Token::Uint((self.kind as u8).into()),
+                Token::FixedBytes(self.commitment.as_bytes().to_vec()),
+                Token::Uint(self.replay_protection.nonce.into()),
+                Token::Uint(self.replay_protection.expires_at.into()),
+            ],
+        )
+    }
+}
+
+/// A signed balance commitment, along with its opening that is only returned to the caller
+#[derive(Debug, Encode, Decode, Clone, scale_info::TypeInfo)]
+pub struct BalanceCommitmentMessage {
+    pub message: BalanceProverMessage,
+    /// The committed amount
+    pub amount: Balance,
+    /// The salt of the commitment
+    pub salt: Vec<u8>,
+}
+
 #[derive(Debug, Encode, Decode, Clone, scale_info::TypeInfo)]
 pub struct BalanceProverMessage {
     pub encoded_request: EncodedMessage,
+    /// The digest that has been signed
+    pub digest: Vec<u8>,
+    /// The scheme used to compute the digest
+    pub scheme: SignatureScheme,
     pub signature: Vec<u8>,
 }
 
@@ -71,7 +899,151 @@ impl From<SignedMessage> for BalanceProverMessage {
     fn from(signed_msg: SignedMessage) -> Self {
         Self {
             encoded_request: signed_msg.encoded_msg,
+            digest: signed_msg.digest.as_bytes().to_vec(),
+            scheme: signed_msg.scheme,
             signature: signed_msg.signature.to_vec(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KINDS: [BalanceKind; 5] = [
+        BalanceKind::Free,
+        BalanceKind::Reserved,
+        BalanceKind::Frozen,
+        BalanceKind::Transferable,
+        BalanceKind::Total,
+    ];
+
+    // Decodes the balance of every kind, in the order of `KINDS`
+    fn decode_all(layout: BalanceValueLayout, value: &[u8]) -> Vec<Balance> {
+        KINDS
+            .iter()
+            .map(|kind| layout.decode_balance(value, *kind).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn decodes_account_info() {
+        // nonce, consumers, providers, sufficients, then free, reserved, frozen and flags
+        let value = (
+            (7u32, 1u32, 1u32, 0u32),
+            (1_000u128, 300u128, 500u128, 1u128 << 127),
+        )
+            .encode();
+
+        // The frozen balance first applies to the reserved balance
+        assert_eq!(
+            decode_all(BalanceValueLayout::AccountInfo, &value),
+            vec![1_000, 300, 500, 800, 1_300]
+        );
+    }
+
+    #[test]
+    fn decodes_legacy_account_info() {
+        // nonce, consumers, providers, sufficients, then free, reserved, misc_frozen and fee_frozen
+        let value = (
+            (7u32, 1u32, 1u32, 0u32),
+            (1_000u128, 300u128, 400u128, 600u128),
+        )
+            .encode();
+
+        assert_eq!(
+            decode_all(BalanceValueLayout::LegacyAccountInfo, &value),
+            vec![1_000, 300, 600, 400, 1_300]
+        );
+    }
+
+    #[test]
+    fn decodes_leading_balance() {
+        // The fields following the balance, e.g. the status and reason of an asset account, are ignored
+        let value = (1_000u128, 0u8, 1u8).encode();
+
+        assert_eq!(
+            decode_all(BalanceValueLayout::Balance, &value),
+            vec![1_000, 0, 0, 1_000, 1_000]
+        );
+        assert_eq!(
+            decode_all(BalanceValueLayout::AssetAccount, &value),
+            vec![1_000, 0, 0, 1_000, 1_000]
+        );
+    }
+
+    #[test]
+    fn decodes_orml_account_data() {
+        let value = (1_000u128, 300u128, 400u128).encode();
+
+        // The frozen balance only applies to the free balance
+        assert_eq!(
+            decode_all(BalanceValueLayout::OrmlAccountData, &value),
+            vec![1_000, 300, 400, 600, 1_300]
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_account_info() {
+        let value = ((7u32, 1u32, 1u32, 0u32), 1_000u128).encode();
+
+        assert_eq!(
+            BalanceValueLayout::AccountInfo.decode_balance(&value, BalanceKind::Free),
+            Err(Error::InvalidBalanceDecoding)
+        );
+    }
+
+    #[test]
+    fn significant_digits_floor_the_balance() {
+        let policy = BucketingPolicy::SignificantDigits(2);
+
+        assert_eq!(policy.apply(123_456), 120_000);
+        assert_eq!(policy.apply(99), 99);
+        assert_eq!(policy.apply(7), 7);
+        assert_eq!(policy.apply(0), 0);
+        assert_eq!(
+            BucketingPolicy::SignificantDigits(39).apply(Balance::MAX),
+            Balance::MAX
+        );
+    }
+
+    #[test]
+    fn logarithmic_floors_to_a_power_of_the_base() {
+        let policy = BucketingPolicy::Logarithmic(10);
+
+        assert_eq!(policy.apply(123_456), 100_000);
+        assert_eq!(policy.apply(1), 1);
+        assert_eq!(policy.apply(0), 0);
+        assert_eq!(BucketingPolicy::Logarithmic(2).apply(1_000), 512);
+    }
+
+    #[test]
+    fn fixed_step_floors_to_a_multiple_of_the_step() {
+        let policy = BucketingPolicy::FixedStep(1_000);
+
+        assert_eq!(policy.apply(123_456), 123_000);
+        assert_eq!(policy.apply(999), 0);
+        assert_eq!(BucketingPolicy::Exact.apply(123_456), 123_456);
+    }
+
+    #[test]
+    fn rejects_policies_without_buckets() {
+        for policy in [
+            BucketingPolicy::SignificantDigits(0),
+            BucketingPolicy::Logarithmic(0),
+            BucketingPolicy::Logarithmic(1),
+            BucketingPolicy::FixedStep(0),
+        ] {
+            assert_eq!(policy.validate(), Err(Error::InvalidBucketingPolicy));
+        }
+
+        for policy in [
+            BucketingPolicy::Exact,
+            BucketingPolicy::SignificantDigits(1),
+            BucketingPolicy::Logarithmic(2),
+            BucketingPolicy::FixedStep(1),
+        ] {
+            assert_eq!(policy.validate(), Ok(()));
+        }
+    }
+}
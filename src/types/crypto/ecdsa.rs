@@ -1,9 +1,9 @@
 use alloc::vec::Vec;
 use k256::ecdsa::{SigningKey as SecretKey, VerifyingKey as PublicKey};
 use pink_extension as pink;
-use sp_core::Hasher;
+use sp_core::{Hasher, H256};
 
-use super::hasher::{ContractBlakeTwo256, ContractKeccak256};
+use super::hasher::ContractBlakeTwo256;
 
 /// The length of the secret seed
 pub const SEED_LENGTH: usize = 32;
@@ -60,9 +60,7 @@ impl KeyPairVersion {
     }
 
     pub fn saturating_inc(&mut self) {
-        if self.0 < u32::MAX {
-            self.0 += 1;
-        }
+        self.0 = self.0.saturating_add(1);
     }
 
     pub fn to_vec(&self) -> Vec<u8> {
@@ -128,8 +126,8 @@ impl ContractKeyPair {
         }
     }
 
-    pub fn sign(&self, message: &[u8]) -> Signature {
-        let msg_hash = ContractKeccak256::hash(message);
+    /// Signs a 32 bytes message hash
+    pub fn sign_prehash(&self, msg_hash: &H256) -> Signature {
         let recsig = self
             .secret
             .sign_prehash_recoverable(msg_hash.as_bytes())
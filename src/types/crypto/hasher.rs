@@ -1,15 +1,12 @@
 use alloc::vec::Vec;
 use ink::env::hash::{Blake2x128, Blake2x256, CryptoHash, Keccak256};
-use scale::MaxEncodedLen;
 use sp_core::Hasher;
 
 pub trait StorageHasher {
     fn hash(x: &[u8]) -> Vec<u8>;
-    fn max_len<K: MaxEncodedLen>() -> usize;
 }
 
 #[derive(PartialEq, Eq, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ContractBlake2_128Concat;
 
 impl StorageHasher for ContractBlake2_128Concat {
@@ -18,14 +15,9 @@ impl StorageHasher for ContractBlake2_128Concat {
         Blake2x128::hash(x, &mut x_hash);
         x_hash.iter().chain(x.iter()).cloned().collect::<Vec<_>>()
     }
-
-    fn max_len<K: MaxEncodedLen>() -> usize {
-        K::max_encoded_len().saturating_add(16)
-    }
 }
 
 #[derive(PartialEq, Eq, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ContractTwox64Concat;
 
 impl StorageHasher for ContractTwox64Concat {
@@ -36,14 +28,58 @@ impl StorageHasher for ContractTwox64Concat {
             .cloned()
             .collect::<Vec<_>>()
     }
+}
+
+#[derive(PartialEq, Eq, Clone)]
+pub struct ContractIdentity;
+
+impl StorageHasher for ContractIdentity {
+    fn hash(x: &[u8]) -> Vec<u8> {
+        x.to_vec()
+    }
+}
+
+#[derive(PartialEq, Eq, Clone)]
+pub struct ContractTwox128;
+
+impl StorageHasher for ContractTwox128 {
+    fn hash(x: &[u8]) -> Vec<u8> {
+        sp_core::hashing::twox_128(x).to_vec()
+    }
+}
+
+#[derive(PartialEq, Eq, Clone)]
+pub struct ContractTwox256;
 
-    fn max_len<K: MaxEncodedLen>() -> usize {
-        K::max_encoded_len().saturating_add(8)
+impl StorageHasher for ContractTwox256 {
+    fn hash(x: &[u8]) -> Vec<u8> {
+        sp_core::hashing::twox_256(x).to_vec()
+    }
+}
+
+#[derive(PartialEq, Eq, Clone)]
+pub struct ContractBlake2_128;
+
+impl StorageHasher for ContractBlake2_128 {
+    fn hash(x: &[u8]) -> Vec<u8> {
+        let mut x_hash = [0u8; 16];
+        Blake2x128::hash(x, &mut x_hash);
+        x_hash.to_vec()
+    }
+}
+
+#[derive(PartialEq, Eq, Clone)]
+pub struct ContractBlake2_256;
+
+impl StorageHasher for ContractBlake2_256 {
+    fn hash(x: &[u8]) -> Vec<u8> {
+        let mut x_hash = [0u8; 32];
+        Blake2x256::hash(x, &mut x_hash);
+        x_hash.to_vec()
     }
 }
 
 #[derive(PartialEq, Eq, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ContractKeccak256;
 
 impl Hasher for ContractKeccak256 {
@@ -59,7 +95,6 @@ impl Hasher for ContractKeccak256 {
 }
 
 #[derive(PartialEq, Eq, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ContractBlakeTwo256;
 
 impl Hasher for ContractBlakeTwo256 {
@@ -73,3 +108,57 @@ impl Hasher for ContractBlakeTwo256 {
         output.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use sp_core::{hashing, H256};
+
+    const DATA: &[u8] = b"Balances";
+
+    #[test]
+    fn storage_hashers_match_frame_hashers() {
+        assert_eq!(ContractBlake2_128::hash(DATA), hashing::blake2_128(DATA));
+        assert_eq!(ContractBlake2_256::hash(DATA), hashing::blake2_256(DATA));
+        assert_eq!(ContractTwox128::hash(DATA), hashing::twox_128(DATA));
+        assert_eq!(ContractTwox256::hash(DATA), hashing::twox_256(DATA));
+        assert_eq!(ContractIdentity::hash(DATA), DATA);
+    }
+
+    #[test]
+    fn concat_hashers_append_the_key() {
+        assert_eq!(
+            ContractBlake2_128Concat::hash(DATA),
+            [&hashing::blake2_128(DATA)[..], DATA].concat()
+        );
+        assert_eq!(
+            ContractTwox64Concat::hash(DATA),
+            [&hashing::twox_64(DATA)[..], DATA].concat()
+        );
+    }
+
+    #[test]
+    fn twox128_of_storage_prefixes() {
+        assert_eq!(
+            ContractTwox128::hash(b"System"),
+            hex!("26aa394eea5630e07c48ae0c9558cef7")
+        );
+        assert_eq!(
+            ContractTwox128::hash(b"Assets"),
+            hex!("682a59d51ab9e48a8c8cc418ff9708d2")
+        );
+    }
+
+    #[test]
+    fn trie_hashers_match_substrate_hashers() {
+        assert_eq!(
+            ContractBlakeTwo256::hash(DATA),
+            H256(hashing::blake2_256(DATA))
+        );
+        assert_eq!(
+            ContractKeccak256::hash(DATA),
+            H256(hashing::keccak_256(DATA))
+        );
+    }
+}
@@ -1,2 +1,3 @@
 pub mod ecdsa;
 pub mod hasher;
+pub mod multi_signature;
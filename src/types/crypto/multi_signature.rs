@@ -0,0 +1,175 @@
+use alloc::vec::Vec;
+use ink::primitives::AccountId;
+use scale::{Decode, Encode};
+use sp_core::{ecdsa, ed25519, hashing::blake2_256, sr25519, Pair};
+
+/// The prefix that wallets add to raw payloads before signing them
+const WRAPPED_BYTES_PREFIX: &[u8] = b"<Bytes>";
+
+/// The postfix that wallets add to raw payloads before signing them
+const WRAPPED_BYTES_POSTFIX: &[u8] = b"</Bytes>";
+
+/// The type of the key that signs on behalf of a Substrate account, as in `MultiSignature`
+#[derive(Debug, Encode, Decode, Clone, Copy, scale_info::TypeInfo)]
+pub enum SignerType {
+    Ed25519,
+    Sr25519,
+    Ecdsa,
+}
+
+/// Verifies the signature of `account` over `message`.
+///
+/// The message may be signed either as is or wrapped in `<Bytes>...</Bytes>`, as wallets do for raw payloads.
+pub fn verify_account_signature(
+    account: &AccountId,
+    message: &[u8],
+    signature: &[u8],
+    signer_type: SignerType,
+) -> bool {
+    let mut wrapped_message = Vec::with_capacity(
+        WRAPPED_BYTES_PREFIX.len() + message.len() + WRAPPED_BYTES_POSTFIX.len(),
+    );
+    wrapped_message.extend_from_slice(WRAPPED_BYTES_PREFIX);
+    wrapped_message.extend_from_slice(message);
+    wrapped_message.extend_from_slice(WRAPPED_BYTES_POSTFIX);
+
+    verify(account, message, signature, signer_type)
+        || verify(account, &wrapped_message, signature, signer_type)
+}
+
+fn verify(account: &AccountId, message: &[u8], signature: &[u8], signer_type: SignerType) -> bool {
+    let account: &[u8; 32] = account.as_ref();
+
+    match signer_type {
+        SignerType::Ed25519 => match ed25519::Signature::from_slice(signature) {
+            Some(signature) => {
+                ed25519::Pair::verify(&signature, message, &ed25519::Public::from_raw(*account))
+            }
+            None => false,
+        },
+        SignerType::Sr25519 => match sr25519::Signature::from_slice(signature) {
+            Some(signature) => {
+                sr25519::Pair::verify(&signature, message, &sr25519::Public::from_raw(*account))
+            }
+            None => false,
+        },
+        // The account of an ECDSA key is the blake2 hash of the compressed public key
+        SignerType::Ecdsa => ecdsa::Signature::from_slice(signature)
+            .and_then(|signature| signature.recover(message))
+            .map(|public| &blake2_256(public.as_ref()) == account)
+            .unwrap_or(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &[u8] = b"balances-prover";
+
+    fn wrapped(message: &[u8]) -> Vec<u8> {
+        [WRAPPED_BYTES_PREFIX, message, WRAPPED_BYTES_POSTFIX].concat()
+    }
+
+    // Signs the raw and the wrapped message and checks both signatures, then checks that a signature
+    // over another payload does not verify
+    fn check<P: Pair>(pair: &P, account: AccountId, signer_type: SignerType)
+    where
+        P::Signature: AsRef<[u8]>,
+    {
+        let raw = pair.sign(MESSAGE);
+        assert!(verify_account_signature(
+            &account,
+            MESSAGE,
+            raw.as_ref(),
+            signer_type
+        ));
+
+        let wrapped = pair.sign(&wrapped(MESSAGE));
+        assert!(verify_account_signature(
+            &account,
+            MESSAGE,
+            wrapped.as_ref(),
+            signer_type
+        ));
+
+        let other = pair.sign(b"another payload");
+        assert!(!verify_account_signature(
+            &account,
+            MESSAGE,
+            other.as_ref(),
+            signer_type
+        ));
+    }
+
+    #[test]
+    fn verifies_ed25519_signatures() {
+        let pair = ed25519::Pair::from_seed(&[1u8; 32]);
+        check(&pair, AccountId::from(pair.public().0), SignerType::Ed25519);
+    }
+
+    #[test]
+    fn verifies_sr25519_signatures() {
+        let pair = sr25519::Pair::from_seed(&[1u8; 32]);
+        check(&pair, AccountId::from(pair.public().0), SignerType::Sr25519);
+    }
+
+    #[test]
+    fn verifies_ecdsa_signatures_against_the_blake2_account() {
+        let pair = ecdsa::Pair::from_seed(&[1u8; 32]);
+        let account = AccountId::from(blake2_256(pair.public().as_ref()));
+        check(&pair, account, SignerType::Ecdsa);
+    }
+
+    #[test]
+    fn rejects_signatures_by_other_keys() {
+        let signer = ed25519::Pair::from_seed(&[1u8; 32]);
+        let other = ed25519::Pair::from_seed(&[2u8; 32]);
+        let signature = signer.sign(MESSAGE);
+        assert!(!verify_account_signature(
+            &AccountId::from(other.public().0),
+            MESSAGE,
+            signature.as_ref(),
+            SignerType::Ed25519
+        ));
+
+        let signer = sr25519::Pair::from_seed(&[1u8; 32]);
+        let other = sr25519::Pair::from_seed(&[2u8; 32]);
+        let signature = signer.sign(MESSAGE);
+        assert!(!verify_account_signature(
+            &AccountId::from(other.public().0),
+            MESSAGE,
+            signature.as_ref(),
+            SignerType::Sr25519
+        ));
+
+        let signer = ecdsa::Pair::from_seed(&[1u8; 32]);
+        let other = ecdsa::Pair::from_seed(&[2u8; 32]);
+        let signature = signer.sign(MESSAGE);
+        assert!(!verify_account_signature(
+            &AccountId::from(blake2_256(other.public().as_ref())),
+            MESSAGE,
+            signature.as_ref(),
+            SignerType::Ecdsa
+        ));
+    }
+
+    #[test]
+    fn rejects_signatures_of_the_wrong_type() {
+        let pair = ed25519::Pair::from_seed(&[1u8; 32]);
+        let signature = pair.sign(MESSAGE);
+
+        assert!(!verify_account_signature(
+            &AccountId::from(pair.public().0),
+            MESSAGE,
+            signature.as_ref(),
+            SignerType::Sr25519
+        ));
+        assert!(!verify_account_signature(
+            &AccountId::from(pair.public().0),
+            MESSAGE,
+            signature.as_ref(),
+            SignerType::Ecdsa
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+use super::{balances::EvmTarget, crypto::hasher::ContractKeccak256};
+use alloc::{string::String, vec, vec::Vec};
+use ethabi::{encode as abi_encode, Token};
+use scale::{Decode, Encode};
+use sp_core::{Hasher, H256};
+
+/// The EIP-712 domain type
+const DOMAIN_TYPE: &str =
+    "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+
+/// The EIP-712 domain of the contract that verifies the signed requests.
+///
+/// The `chainId` and `verifyingContract` of the domain are the ones of the EVM target of the prover,
+/// so that they are always the ones bound by the other signature schemes.
+#[derive(Debug, Encode, Decode, Clone, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct Eip712Domain {
+    pub name: String,
+    pub version: String,
+}
+
+impl Eip712Domain {
+    /// The domain separator for the EVM target, i.e. `hashStruct(eip712Domain)`
+    pub fn separator(&self, target: &EvmTarget) -> H256 {
+        hash_struct(
+            DOMAIN_TYPE,
+            vec![
+                hash_bytes(self.name.as_bytes()),
+                hash_bytes(self.version.as_bytes()),
+                Token::Uint(target.chain_id.into()),
+                Token::Address(target.verifying_contract.into()),
+            ],
+        )
+    }
+
+    /// The digest to sign for the EVM target, i.e. `keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(message))`
+    pub fn digest(&self, target: &EvmTarget, struct_hash: &H256) -> H256 {
+        let mut message = Vec::with_capacity(66);
+        message.extend_from_slice(b"\x19\x01");
+        message.extend_from_slice(self.separator(target).as_bytes());
+        message.extend_from_slice(struct_hash.as_bytes());
+
+        ContractKeccak256::hash(&message)
+    }
+}
+
+/// A struct that can be hashed as EIP-712 typed structured data
+pub trait Eip712Struct {
+    /// `hashStruct(s) = keccak256(typeHash ‖ encodeData(s))`
+    fn struct_hash(&self) -> H256;
+}
+
+/// Hashes the encoded data of a struct of the given EIP-712 type
+pub fn hash_struct(struct_type: &str, data: Vec<Token>) -> H256 {
+    let mut tokens = vec![hash_bytes(struct_type.as_bytes())];
+    tokens.extend(data);
+
+    ContractKeccak256::hash(&abi_encode(&tokens))
+}
+
+/// Encodes a dynamic `bytes` or `string` value as EIP-712 data
+pub fn hash_bytes(value: &[u8]) -> Token {
+    Token::FixedBytes(ContractKeccak256::hash(value).as_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::evm::Address;
+    use hex_literal::hex;
+
+    // The `Mail` example of the EIP-712 specification
+    const PERSON_TYPE: &str = "Person(string name,address wallet)";
+    const MAIL_TYPE: &str =
+        "Mail(Person from,Person to,string contents)Person(string name,address wallet)";
+
+    fn address(bytes: [u8; 20]) -> Address {
+        Address::decode(&mut &bytes[..]).unwrap()
+    }
+
+    fn person(name: &str, wallet: [u8; 20]) -> Token {
+        let hash = hash_struct(
+            PERSON_TYPE,
+            vec![hash_bytes(name.as_bytes()), Token::Address(wallet.into())],
+        );
+        Token::FixedBytes(hash.as_bytes().to_vec())
+    }
+
+    fn mail_domain() -> (Eip712Domain, EvmTarget) {
+        let domain = Eip712Domain {
+            name: "Ether Mail".into(),
+            version: "1".into(),
+        };
+        let target = EvmTarget {
+            chain_id: 1,
+            verifying_contract: address(hex!("CcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC")),
+        };
+        (domain, target)
+    }
+
+    #[test]
+    fn domain_separator_binds_the_evm_target() {
+        let (domain, target) = mail_domain();
+
+        assert_eq!(
+            domain.separator(&target),
+            H256(hex!(
+                "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+            ))
+        );
+
+        let other_chain = EvmTarget {
+            chain_id: 2,
+            ..target
+        };
+        assert_ne!(domain.separator(&other_chain), domain.separator(&target));
+    }
+
+    #[test]
+    fn digest_matches_the_specification() {
+        let (domain, target) = mail_domain();
+        let mail = hash_struct(
+            MAIL_TYPE,
+            vec![
+                person("Cow", hex!("CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826")),
+                person("Bob", hex!("bBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB")),
+                hash_bytes(b"Hello, Bob!"),
+            ],
+        );
+        assert_eq!(
+            mail,
+            H256(hex!(
+                "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+            ))
+        );
+
+        let digest = domain.digest(&target, &mail);
+        assert_eq!(
+            digest,
+            H256(hex!(
+                "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+            ))
+        );
+
+        // The signature of the specification, with `v` = 28
+        let signature = hex!(
+            "4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d"
+            "07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562"
+            "1c"
+        );
+        assert_eq!(
+            Address::recover(&digest, &signature),
+            Some(address(hex!("CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826")))
+        );
+    }
+}
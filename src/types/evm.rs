@@ -1,18 +1,20 @@
-use super::crypto::{ecdsa::Signature, hasher::ContractKeccak256};
-use alloc::vec::Vec;
+use super::{
+    crypto::{
+        ecdsa::{Signature, SIGNATURE_LENGTH},
+        hasher::ContractKeccak256,
+    },
+    eip712::Eip712Domain,
+};
+use alloc::{format, vec::Vec};
 use ethabi::{ethereum_types::H160, Address as EvmAddress};
-use k256::ecdsa::VerifyingKey;
+use k256::ecdsa::{RecoveryId, Signature as EcdsaSignature, VerifyingKey};
 use scale::{Decode, Encode};
-use sp_core::Hasher;
+use sp_core::{Hasher, H256};
 
 pub type EncodedMessage = Vec<u8>;
 
-pub trait ABIEncode {
-    fn abi_encode(&self) -> EncodedMessage;
-}
-
 /// An EVM address
-#[derive(Debug, Encode, Decode, Clone, Copy, scale_info::TypeInfo)]
+#[derive(Debug, Encode, Decode, Clone, Copy, PartialEq, Eq, scale_info::TypeInfo)]
 #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
 pub struct Address([u8; 20]);
 
@@ -38,7 +40,144 @@ impl From<VerifyingKey> for Address {
     }
 }
 
+impl Address {
+    /// Recovers the address that signed the digest, the recovery id can be either 0/1 or 27/28
+    pub fn recover(digest: &H256, signature: &[u8]) -> Option<Address> {
+        if signature.len() != SIGNATURE_LENGTH {
+            return None;
+        }
+        let v = signature[64];
+        let recovery_id = RecoveryId::from_byte(if v >= 27 { v - 27 } else { v })?;
+        let signature = EcdsaSignature::from_slice(&signature[..64]).ok()?;
+        let key =
+            VerifyingKey::recover_from_prehash(digest.as_bytes(), &signature, recovery_id).ok()?;
+
+        Some(key.into())
+    }
+}
+
+/// The scheme used to hash a request before signing it
+#[derive(Debug, Encode, Decode, Clone, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub enum SignatureScheme {
+    /// Signs `keccak256(abi.encode(request))`
+    Keccak256,
+    /// Signs the request as EIP-712 typed structured data within the given domain,
+    /// whose chain id and verifying contract are the ones of the EVM target
+    Eip712(Eip712Domain),
+    /// EIP-191 version `0x45`, signs `toEthSignedMessageHash(keccak256(abi.encode(request)))`
+    PersonalSign,
+    /// EIP-191 version `0x00`, signs `toDataWithIntendedValidatorHash(validator, keccak256(abi.encode(request)))`
+    IntendedValidator(Address),
+}
+
+/// The format of the recovery id within the signature
+#[derive(Debug, Encode, Decode, Clone, Copy, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub enum RecoveryIdFormat {
+    /// The recovery id is either 0 or 1
+    Raw,
+    /// The recovery id is either 27 or 28, as expected by `ecrecover`
+    Ethereum,
+}
+
+impl RecoveryIdFormat {
+    /// Formats the recovery id of a signature
+    pub fn apply(&self, mut signature: Signature) -> Signature {
+        if let RecoveryIdFormat::Ethereum = self {
+            signature[64] += 27;
+        }
+        signature
+    }
+}
+
+/// EIP-191 version `0x45`, `keccak256("\x19Ethereum Signed Message:\n" ‖ len(message) ‖ message)`
+pub fn eth_signed_message_hash(message: &[u8]) -> H256 {
+    let mut data = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    data.extend_from_slice(message);
+
+    ContractKeccak256::hash(&data)
+}
+
+/// EIP-191 version `0x00`, `keccak256("\x19\x00" ‖ validator ‖ data)`
+pub fn intended_validator_hash(validator: &Address, data: &[u8]) -> H256 {
+    let mut message = Vec::with_capacity(22 + data.len());
+    message.extend_from_slice(b"\x19\x00");
+    message.extend_from_slice(&validator.0);
+    message.extend_from_slice(data);
+
+    ContractKeccak256::hash(&message)
+}
+
 pub struct SignedMessage {
     pub signature: Signature,
+    pub digest: H256,
+    pub scheme: SignatureScheme,
     pub encoded_msg: EncodedMessage,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::crypto::ecdsa::{ContractKeyPair, ContractSeed};
+    use hex_literal::hex;
+
+    fn pair() -> ContractKeyPair {
+        ContractSeed {
+            seed: [1u8; 32],
+            version: 0,
+        }
+        .into()
+    }
+
+    #[test]
+    fn eth_signed_message_hash_matches_hash_message() {
+        // `hashMessage("Hello World")` of ethers.js
+        assert_eq!(
+            eth_signed_message_hash(b"Hello World"),
+            H256(hex!(
+                "a1de988600a42c4b4ab089b619297c17d53cffae5d5120d82d8a92d0bb3b78f2"
+            ))
+        );
+    }
+
+    #[test]
+    fn intended_validator_hash_prefixes_the_validator() {
+        let validator = Address(hex!("CcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"));
+        let data = [7u8; 32];
+
+        let mut message = hex!("1900").to_vec();
+        message.extend_from_slice(&validator.0);
+        message.extend_from_slice(&data);
+        assert_eq!(
+            intended_validator_hash(&validator, &data),
+            ContractKeccak256::hash(&message)
+        );
+    }
+
+    #[test]
+    fn recovers_raw_and_ethereum_recovery_ids() {
+        let pair = pair();
+        let signer = Address::from(pair.public());
+        let digest = eth_signed_message_hash(b"Hello World");
+
+        let raw = RecoveryIdFormat::Raw.apply(pair.sign_prehash(&digest));
+        let ethereum = RecoveryIdFormat::Ethereum.apply(pair.sign_prehash(&digest));
+        assert!(raw[64] < 2);
+        assert_eq!(ethereum[64], raw[64] + 27);
+
+        assert_eq!(Address::recover(&digest, &raw), Some(signer));
+        assert_eq!(Address::recover(&digest, &ethereum), Some(signer));
+    }
+
+    #[test]
+    fn rejects_malformed_signatures() {
+        let pair = pair();
+        let digest = eth_signed_message_hash(b"Hello World");
+        let mut signature = pair.sign_prehash(&digest);
+
+        assert_eq!(Address::recover(&digest, &signature[..64]), None);
+        signature[64] = 31;
+        assert_eq!(Address::recover(&digest, &signature), None);
+    }
+}
@@ -0,0 +1,123 @@
+use super::{header::Header, Error, Result};
+use alloc::vec::Vec;
+use scale::{Decode, Encode};
+use sp_core::H256;
+
+/// The GRANDPA consensus engine id
+pub const GRANDPA_ENGINE_ID: [u8; 4] = *b"FRNK";
+
+/// An ed25519 GRANDPA authority public key
+pub type AuthorityId = [u8; 32];
+
+/// An ed25519 GRANDPA authority signature
+pub type AuthoritySignature = [u8; 64];
+
+/// The weight of a GRANDPA authority
+pub type AuthorityWeight = u64;
+
+/// The id of a GRANDPA authority set
+pub type SetId = u64;
+
+/// The GRANDPA authority set trusted by the contract
+#[derive(Debug, Encode, Decode, Clone, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct AuthoritySet {
+    pub set_id: SetId,
+    pub authorities: Vec<(AuthorityId, AuthorityWeight)>,
+    /// The number of the block that enacted the set, changes scheduled up to it are already applied
+    pub enacted_at: u32,
+}
+
+impl AuthoritySet {
+    /// Ensures the set has authorities with some weight, since an empty set would need no votes
+    pub fn validate(&self) -> Result<()> {
+        if self.authorities.iter().all(|(_, weight)| *weight == 0) {
+            return Err(Error::EmptyAuthoritySet);
+        }
+
+        Ok(())
+    }
+
+    /// The weight of an authority within the set
+    pub fn weight_of(&self, id: &AuthorityId) -> Option<AuthorityWeight> {
+        self.authorities
+            .iter()
+            .find(|(authority, _)| authority == id)
+            .map(|(_, weight)| *weight)
+    }
+
+    /// The minimum weight of the votes needed to finalize a block, i.e. more than 2/3 of the total weight
+    pub fn threshold(&self) -> AuthorityWeight {
+        let total = self
+            .authorities
+            .iter()
+            .fold(0u64, |total, (_, weight)| total.saturating_add(*weight));
+        let faulty = total.saturating_sub(1) / 3;
+
+        total - faulty
+    }
+}
+
+/// A GRANDPA precommit for a block
+#[derive(Debug, Encode, Decode, Clone)]
+pub struct Precommit {
+    pub target_hash: H256,
+    pub target_number: u32,
+}
+
+/// A precommit signed by a GRANDPA authority
+#[derive(Debug, Encode, Decode, Clone)]
+pub struct SignedPrecommit {
+    pub precommit: Precommit,
+    pub signature: AuthoritySignature,
+    pub id: AuthorityId,
+}
+
+/// A GRANDPA commit for a block
+#[derive(Debug, Encode, Decode, Clone)]
+pub struct Commit {
+    pub target_hash: H256,
+    pub target_number: u32,
+    pub precommits: Vec<SignedPrecommit>,
+}
+
+/// A GRANDPA justification for block finality, i.e. `sc_consensus_grandpa::GrandpaJustification`
+#[derive(Debug, Encode, Decode, Clone)]
+pub struct GrandpaJustification {
+    pub round: u64,
+    pub commit: Commit,
+    pub votes_ancestries: Vec<Header>,
+}
+
+/// The finality proof returned by `grandpa_proveFinality`
+#[derive(Debug, Encode, Decode, Clone)]
+pub struct FinalityProof {
+    /// The hash of the block that is justified
+    pub block: H256,
+    /// The encoded `GrandpaJustification` of the block
+    pub justification: Vec<u8>,
+    /// The headers between the requested block and the justified one
+    pub unknown_headers: Vec<Header>,
+}
+
+/// A change of the GRANDPA authority set
+#[derive(Debug, Encode, Decode, Clone)]
+pub struct ScheduledChange {
+    pub next_authorities: Vec<(AuthorityId, AuthorityWeight)>,
+    pub delay: u32,
+}
+
+/// A GRANDPA consensus digest log, i.e. `sp_consensus_grandpa::ConsensusLog`
+#[derive(Debug, Encode, Decode, Clone)]
+pub enum ConsensusLog {
+    #[codec(index = 1)]
+    ScheduledChange(ScheduledChange),
+    #[codec(index = 2)]
+    ForcedChange(u32, ScheduledChange),
+    #[codec(index = 3)]
+    OnDisabled(u64),
+    #[codec(index = 4)]
+    Pause(u32),
+    #[codec(index = 5)]
+    Resume(u32),
+}
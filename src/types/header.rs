@@ -0,0 +1,115 @@
+use super::{Error, HashAlgorithm, Result};
+use alloc::vec::Vec;
+use scale::{Decode, Encode, Error as CodecError, Input, Output};
+use sp_core::H256;
+
+/// A consensus engine id
+pub type ConsensusEngineId = [u8; 4];
+
+/// A SCALE encoded Substrate block header, i.e. `sp_runtime::generic::Header<u32, _>`.
+///
+/// The header is hashed as it is encoded and only the fields that are read get decoded,
+/// so that digest items unknown to the prover do not prevent its verification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Header {
+    encoded: Vec<u8>,
+}
+
+/// The fields of a header that precede its digest
+#[derive(Debug, Encode, Decode, Clone, PartialEq)]
+pub struct HeaderFields {
+    pub parent_hash: H256,
+    #[codec(compact)]
+    pub number: u32,
+    pub state_root: H256,
+    pub extrinsics_root: H256,
+}
+
+impl Header {
+    pub fn new(encoded: Vec<u8>) -> Self {
+        Self { encoded }
+    }
+
+    /// The hash of the header, i.e. the hash of its encoding
+    pub fn hash(&self, hasher: &HashAlgorithm) -> H256 {
+        hasher.hash(&self.encoded)
+    }
+
+    /// Decodes the fields of the header that precede its digest
+    pub fn fields(&self) -> Result<HeaderFields> {
+        HeaderFields::decode(&mut &*self.encoded).or(Err(Error::InvalidHeader))
+    }
+
+    /// Decodes the digest of the header
+    pub fn digest(&self) -> Result<Digest> {
+        let input = &mut &*self.encoded;
+        HeaderFields::decode(input).or(Err(Error::InvalidHeader))?;
+
+        Digest::decode(input).or(Err(Error::InvalidDigestItem))
+    }
+}
+
+impl Encode for Header {
+    fn size_hint(&self) -> usize {
+        self.encoded.len()
+    }
+
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        dest.write(&self.encoded)
+    }
+}
+
+// Headers within other encoded data (e.g. the ancestries of a justification) have no length prefix,
+// hence their digest is decoded to find where they end
+impl Decode for Header {
+    fn decode<I: Input>(input: &mut I) -> core::result::Result<Self, CodecError> {
+        let mut input = RecordingInput {
+            input,
+            recorded: Vec::new(),
+        };
+        HeaderFields::decode(&mut input)?;
+        Digest::decode(&mut input)?;
+
+        Ok(Self::new(input.recorded))
+    }
+}
+
+// An input that records the bytes read from the inner input
+struct RecordingInput<'a, I> {
+    input: &'a mut I,
+    recorded: Vec<u8>,
+}
+
+impl<I: Input> Input for RecordingInput<'_, I> {
+    fn remaining_len(&mut self) -> core::result::Result<Option<usize>, CodecError> {
+        self.input.remaining_len()
+    }
+
+    fn read(&mut self, into: &mut [u8]) -> core::result::Result<(), CodecError> {
+        self.input.read(into)?;
+        self.recorded.extend_from_slice(into);
+
+        Ok(())
+    }
+}
+
+/// The digest of a block header
+#[derive(Debug, Encode, Decode, Clone, PartialEq)]
+pub struct Digest {
+    pub logs: Vec<DigestItem>,
+}
+
+/// A digest item, i.e. `sp_runtime::generic::DigestItem`
+#[derive(Debug, Encode, Decode, Clone, PartialEq)]
+pub enum DigestItem {
+    #[codec(index = 6)]
+    PreRuntime(ConsensusEngineId, Vec<u8>),
+    #[codec(index = 4)]
+    Consensus(ConsensusEngineId, Vec<u8>),
+    #[codec(index = 5)]
+    Seal(ConsensusEngineId, Vec<u8>),
+    #[codec(index = 0)]
+    Other(Vec<u8>),
+    #[codec(index = 8)]
+    RuntimeEnvironmentUpdated,
+}
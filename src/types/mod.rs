@@ -1,13 +1,20 @@
 use alloc::{string::String, vec::Vec};
+use crypto::hasher::{ContractBlakeTwo256, ContractKeccak256};
+use ink::primitives::AccountId;
 use scale::{Decode, Encode};
+use sp_core::{Hasher, H256};
 
 pub mod access_control;
 pub mod balances;
 pub mod crypto;
+pub mod eip712;
 pub mod evm;
+pub mod grandpa;
+pub mod header;
 pub mod rpc;
 
 #[derive(Debug, PartialEq, Eq, Encode, Decode, scale_info::TypeInfo)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     // A caller account has a bad origin
     BadOrigin,
@@ -25,18 +32,123 @@ pub enum Error {
     HexStringOutOfBounds,
     /// Error in RPC request
     RpcRequestFailed,
-    /// Balance request message has already been signed by the contract
-    RequestAlreadySigned,
     /// Invalid H256
     InvalidHashBytes,
     /// Invalid account balance
     InvalidBalance,
     /// Balance decoding error
     InvalidBalanceDecoding,
+    /// The prover is paused and does not serve any request
+    ProverPaused,
+    /// The prover is read only and refuses to sign requests
+    ProverReadOnly,
+    /// Invalid block number
+    InvalidBlockNumber,
+    /// Failed to decode a block header
+    InvalidHeader,
+    /// Failed to decode a header digest item
+    InvalidDigestItem,
+    /// The snapshot block hash is not the hash of the snapshot header
+    SnapshotBlockHashMismatch,
+    /// The GRANDPA authority set has not been set
+    AuthoritySetNotSet,
+    /// The GRANDPA authority set has no authorities with some weight
+    EmptyAuthoritySet,
+    /// The chain has a trusted authority set, hence the finality of the snapshot must be proven
+    FinalityProofRequired,
+    /// Failed to decode the finality proof
+    InvalidFinalityProof,
+    /// The justification does not justify the expected block
+    JustificationTargetMismatch,
+    /// A precommit of the justification does not vote for the justified block
+    InvalidJustification,
+    /// A precommit is signed by an account that is not in the authority set
+    UnknownGrandpaAuthority,
+    /// Invalid GRANDPA precommit signature
+    InvalidGrandpaSignature,
+    /// The precommits weight does not reach the authority set threshold
+    InsufficientGrandpaVotes,
+    /// The snapshot block is not finalized
+    SnapshotNotFinalized,
+    /// The header does not schedule an authority set change
+    NoAuthoritySetChange,
+    /// The header forces an authority set change, which can only be applied by sudo
+    ForcedAuthoritySetChange,
+    /// The block that enacts the scheduled authority set change is not finalized
+    AuthoritySetChangeNotEnacted,
+    /// The authority set change is scheduled before the trusted authority set was enacted
+    AuthoritySetChangeAlreadyApplied,
+    /// The snapshot is not newer than the stored one
+    SnapshotNotNewer,
+    /// The snapshot is not of the same parachain as the stored one, or not of the relay chain itself
+    SnapshotChainMismatch,
+    /// The relay chain RPC has not been set
+    RelayRpcNotSet,
+    /// The parachain head is not stored in the relay chain state
+    ParachainHeadNotFound,
+    /// Failed to decode the parachain head
+    InvalidParachainHead,
+    /// The parachain head is not the header of the snapshot block
+    ParachainHeadMismatch,
+    /// The state version of the runtime is not supported
+    UnsupportedStateVersion,
+    /// The signature of the Substrate account is not valid
+    InvalidAccountSignature,
+    /// The payload signed by the Substrate account has expired
+    DelegatedProofExpired,
+    /// The claim address must consent to the claim with a signature
+    ClaimConsentRequired,
+    /// The claim consent is not signed by the claim address
+    InvalidClaimConsent,
+    /// The claim consent has expired
+    ClaimConsentExpired,
+    /// The asset is not registered
+    AssetNotFound,
+    /// The asset is already registered
+    AssetAlreadyRegistered,
+    /// The balances of the asset cannot be proven
+    AssetDisabled,
+    /// The default asset cannot be removed
+    DefaultAssetRemoval,
+    /// The chain is not registered
+    ChainNotFound,
+    /// The chain is already registered
+    ChainAlreadyRegistered,
+    /// The chain cannot be removed while some of its assets are registered
+    ChainInUse,
+    /// The balance does not meet the threshold, or any of the balance tiers
+    BalanceBelowThreshold,
+    /// No balance tiers are set for the asset
+    BalanceTiersNotSet,
+    /// The parameter of the bucketing policy does not define any bucket
+    InvalidBucketingPolicy,
+    /// The RPC endpoint has already been added
+    RpcEndpointAlreadyAdded,
+    /// The RPC endpoint is not one of the endpoints
+    RpcEndpointNotFound,
+    /// The RPC quorum must be at least one and at most the number of endpoints
+    InvalidRpcQuorum,
+    /// Not enough RPC endpoints returned the same result
+    RpcQuorumNotReached,
+    /// The id of the RPC response is not the one of the request
+    RpcResponseIdMismatch,
+    /// The read proof is not at the requested block
+    ReadProofBlockMismatch,
+    /// The node has pruned the state of the requested block
+    StateDiscarded(rpc::RpcErrorInfo),
+    /// The node does not know the requested block
+    UnknownBlock(rpc::RpcErrorInfo),
+    /// The node does not expose the RPC method
+    RpcMethodNotFound(rpc::RpcErrorInfo),
+    /// The node returned an error
+    RpcNodeError(rpc::RpcErrorInfo),
 }
 
 pub type Result<T> = core::result::Result<T, Error>;
 
+/// The id of a chain registered in the prover
+pub type ChainId = u32;
+
 #[derive(Debug, Encode, Decode, Clone)]
 #[cfg_attr(
     feature = "std",
@@ -47,6 +159,73 @@ pub struct SnapshotCommitment {
     pub block_hash: Vec<u8>,
     pub state_root: Vec<u8>,
     pub hasher: HashAlgorithm,
+    /// The trie layout of the state, declared when the snapshot is registered
+    pub state_version: StateVersion,
+    /// For parachain snapshots, the relay chain block through which the snapshot is proven
+    pub relay_anchor: Option<RelayChainAnchor>,
+}
+
+/// A block submitted as the snapshot of a chain, along with its SCALE encoded header.
+///
+/// The height and the state root of the snapshot are read from the header, once it is checked to hash to the block hash.
+/// That check only binds the header to the given block hash: the block is proven to be of the chain by its finality,
+/// hence snapshots registered without a finality proof (on registration, or by sudo while the chain has no
+/// authority set) are trusted from sudo.
+#[derive(Debug, Encode, Decode, Clone)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct SnapshotBlock {
+    pub block_hash: Vec<u8>,
+    pub header: Vec<u8>,
+    pub hasher: HashAlgorithm,
+    /// The state version of the runtime at the block, which is not verified against the chain.
+    ///
+    /// It is trusted from sudo on registration and on forced updates, where the `snapshot_block` query gives the one
+    /// declared by the runtime, and ignored on permissionless updates, which keep the one of the stored snapshot.
+    pub state_version: StateVersion,
+    /// For parachain snapshots, the relay chain block through which the snapshot is proven
+    pub relay_block: Option<RelayBlock>,
+}
+
+/// A relay chain block submitted along with its SCALE encoded header and the read proof of the parachain head
+#[derive(Debug, Encode, Decode, Clone)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct RelayBlock {
+    pub para_id: u32,
+    pub block_hash: Vec<u8>,
+    pub header: Vec<u8>,
+    pub hasher: HashAlgorithm,
+    pub state_version: StateVersion,
+    /// The trie nodes of the relay chain read proof of `Paras::Heads(para_id)`
+    pub head_proof: Vec<Vec<u8>>,
+}
+
+/// A relay chain block that includes the head of a parachain
+#[derive(Debug, Encode, Decode, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+)]
+pub struct RelayChainAnchor {
+    pub para_id: u32,
+    pub relay_height: u32,
+    pub relay_block_hash: Vec<u8>,
+    pub relay_state_root: Vec<u8>,
+    pub relay_hasher: HashAlgorithm,
+    pub relay_state_version: StateVersion,
+}
+
+impl RelayChainAnchor {
+    /// The snapshot of the relay chain block
+    pub fn relay_snapshot(&self) -> SnapshotCommitment {
+        SnapshotCommitment {
+            height: self.relay_height,
+            block_hash: self.relay_block_hash.clone(),
+            state_root: self.relay_state_root.clone(),
+            hasher: self.relay_hasher.clone(),
+            state_version: self.relay_state_version,
+            relay_anchor: None,
+        }
+    }
 }
 
 /// Hashing algorithm for the state proof
@@ -62,11 +241,48 @@ pub enum HashAlgorithm {
     Blake2,
 }
 
+impl HashAlgorithm {
+    /// Hashes the data with the hashing algorithm
+    pub fn hash(&self, data: &[u8]) -> H256 {
+        match self {
+            HashAlgorithm::Keccak => ContractKeccak256::hash(data),
+            HashAlgorithm::Blake2 => ContractBlakeTwo256::hash(data),
+        }
+    }
+}
+
+/// The trie layout of the state, i.e. `sp_runtime::StateVersion`
+#[derive(Debug, Encode, Decode, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+)]
+pub enum StateVersion {
+    /// `LayoutV0`, values are always inlined in the trie nodes
+    V0,
+    /// `LayoutV1`, large values are hashed out of the trie nodes
+    V1,
+}
+
+impl TryFrom<u8> for StateVersion {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(StateVersion::V0),
+            1 => Ok(StateVersion::V1),
+            _ => Err(Error::UnsupportedStateVersion),
+        }
+    }
+}
+
 /// Holds the relevant data needed for state proof verification
 #[derive(Debug, Encode, Decode, Clone)]
 pub struct SubstrateStateProof {
     /// Algorithm to use for state proof verification
     pub hasher: HashAlgorithm,
+    /// Trie layout to use for state proof verification
+    pub state_version: StateVersion,
     /// Storage proof for the parachain headers
     pub storage_proof: Vec<Vec<u8>>,
 }
@@ -74,6 +290,48 @@ pub struct SubstrateStateProof {
 #[derive(Debug, Encode, Decode, Clone, Copy, scale_info::TypeInfo)]
 #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
 pub enum ProverStatus {
+    /// Refuses to serve any balance request
     Paused,
+    /// Serves balance requests and signs them
     Live,
+    /// Serves balance queries but refuses to sign them
+    ReadOnly,
+}
+
+/// The prover status along with who changed it last and when
+#[derive(Debug, Encode, Decode, Clone, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct ProverStatusInfo {
+    pub status: ProverStatus,
+    /// The account that last updated the status
+    pub updated_by: AccountId,
+    /// The timestamp (in milliseconds) of the last status update
+    pub updated_at: u64,
+}
+
+impl ProverStatusInfo {
+    pub fn new(status: ProverStatus, updated_by: AccountId, updated_at: u64) -> Self {
+        Self {
+            status,
+            updated_by,
+            updated_at,
+        }
+    }
+
+    /// Ensures the prover serves balance queries
+    pub fn ensure_serving(&self) -> Result<()> {
+        match self.status {
+            ProverStatus::Paused => Err(Error::ProverPaused),
+            ProverStatus::Live | ProverStatus::ReadOnly => Ok(()),
+        }
+    }
+
+    /// Ensures the prover signs balance requests
+    pub fn ensure_signing(&self) -> Result<()> {
+        match self.status {
+            ProverStatus::Paused => Err(Error::ProverPaused),
+            ProverStatus::ReadOnly => Err(Error::ProverReadOnly),
+            ProverStatus::Live => Ok(()),
+        }
+    }
 }
@@ -1,13 +1,60 @@
-use alloc::vec::Vec;
-use scale::Encode;
+use super::Error;
+use alloc::{string::String, vec::Vec};
+use scale::{Decode, Encode};
 use serde::Deserialize;
 
-#[derive(Deserialize, Encode, Clone, Debug, PartialEq)]
-pub struct ReadProof<'a> {
+/// The code of the JSON-RPC error of a method that does not exist
+const METHOD_NOT_FOUND: i32 = -32601;
+
+/// A JSON-RPC 2.0 response, which holds either the result or the error of the request
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct RpcResponse<'a, T> {
     pub jsonrpc: &'a str,
+    pub result: Option<T>,
     #[serde(borrow)]
-    pub result: ReadProofAtBlock<'a>,
-    pub id: u32,
+    pub error: Option<RpcError<'a>>,
+    /// Missing when the node could not read the id of the request
+    pub id: Option<u32>,
+}
+
+/// A JSON-RPC 2.0 error object
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct RpcError<'a> {
+    pub code: i32,
+    pub message: &'a str,
+    /// The additional information of the error, nodes provide it as a string
+    pub data: Option<&'a str>,
+}
+
+/// The details of an error returned by a node
+#[derive(Debug, PartialEq, Eq, Encode, Decode, scale_info::TypeInfo)]
+pub struct RpcErrorInfo {
+    pub code: i32,
+    pub message: String,
+    pub data: Option<String>,
+}
+
+impl From<RpcError<'_>> for Error {
+    fn from(error: RpcError<'_>) -> Self {
+        let mentions = |text: &str| {
+            error.message.contains(text) || error.data.is_some_and(|data| data.contains(text))
+        };
+        let info = RpcErrorInfo {
+            code: error.code,
+            message: String::from(error.message),
+            data: error.data.map(String::from),
+        };
+
+        if mentions("State already discarded") {
+            Error::StateDiscarded(info)
+        } else if mentions("UnknownBlock") || mentions("Unknown block") {
+            Error::UnknownBlock(info)
+        } else if error.code == METHOD_NOT_FOUND {
+            Error::RpcMethodNotFound(info)
+        } else {
+            Error::RpcNodeError(info)
+        }
+    }
 }
 
 #[derive(Deserialize, Encode, Clone, Debug, PartialEq)]
@@ -17,3 +64,94 @@ pub struct ReadProofAtBlock<'a> {
     #[serde(borrow)]
     pub proof: Vec<&'a str>,
 }
+
+/// A block header as returned by `chain_getHeader`
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcHeader<'a> {
+    pub parent_hash: &'a str,
+    pub number: &'a str,
+    pub state_root: &'a str,
+    pub extrinsics_root: &'a str,
+    #[serde(borrow)]
+    pub digest: RpcDigest<'a>,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(bound(deserialize = "Vec<&'a str>: Deserialize<'de>"))]
+pub struct RpcDigest<'a> {
+    #[serde(borrow)]
+    pub logs: Vec<&'a str>,
+}
+
+/// The runtime version as returned by `state_getRuntimeVersion`, other fields are ignored
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeVersion {
+    /// Missing for runtimes that predate state versions
+    pub state_version: Option<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(code: i32, message: &str, data: Option<&str>) -> Error {
+        RpcError {
+            code,
+            message,
+            data,
+        }
+        .into()
+    }
+
+    fn info(code: i32, message: &str, data: Option<&str>) -> RpcErrorInfo {
+        RpcErrorInfo {
+            code,
+            message: String::from(message),
+            data: data.map(String::from),
+        }
+    }
+
+    #[test]
+    fn classifies_discarded_states() {
+        let message = "Client error: UnknownBlock: State already discarded for 0x01";
+        assert_eq!(
+            classify(4003, message, None),
+            Error::StateDiscarded(info(4003, message, None))
+        );
+
+        let data = Some("State already discarded for 0x01");
+        assert_eq!(
+            classify(1002, "Error", data),
+            Error::StateDiscarded(info(1002, "Error", data))
+        );
+    }
+
+    #[test]
+    fn classifies_unknown_blocks() {
+        let message = "Client error: UnknownBlock: Header was not found";
+        assert_eq!(
+            classify(4003, message, None),
+            Error::UnknownBlock(info(4003, message, None))
+        );
+
+        let data = Some("Unknown block 0x01");
+        assert_eq!(
+            classify(1002, "Error", data),
+            Error::UnknownBlock(info(1002, "Error", data))
+        );
+    }
+
+    #[test]
+    fn classifies_missing_methods_and_other_errors() {
+        assert_eq!(
+            classify(METHOD_NOT_FOUND, "Method not found", None),
+            Error::RpcMethodNotFound(info(METHOD_NOT_FOUND, "Method not found", None))
+        );
+        assert_eq!(
+            classify(-32602, "Invalid params", None),
+            Error::RpcNodeError(info(-32602, "Invalid params", None))
+        );
+    }
+}
@@ -1,13 +1,25 @@
 use crate::types::{
-    balances::{BalanceProverMessage, ProverRequest},
+    balances::{
+        AssetId, BalanceProverMessage, EvmTarget, KeyHasher, StorageKeyComponent, StorageKeyLayout,
+    },
     crypto::{
         ecdsa::ContractKeyPair,
-        hasher::{ContractBlake2_128Concat, ContractTwox64Concat, StorageHasher},
+        hasher::{
+            ContractBlake2_128, ContractBlake2_128Concat, ContractBlake2_256, ContractIdentity,
+            ContractKeccak256, ContractTwox128, ContractTwox256, ContractTwox64Concat,
+            StorageHasher,
+        },
+    },
+    eip712::Eip712Struct,
+    evm::{
+        eth_signed_message_hash, intended_validator_hash, EncodedMessage, RecoveryIdFormat,
+        SignatureScheme, SignedMessage,
     },
-    evm::{EncodedMessage, SignedMessage},
 };
 use alloc::vec::Vec;
-use scale::Encode;
+use ink::primitives::AccountId;
+use scale::{Encode, Output};
+use sp_core::{hashing::twox_128, Hasher, H256};
 
 pub type BalancesStorageKey = Vec<u8>;
 
@@ -18,8 +30,40 @@ pub struct BalanceStorageKeyBuilder {
 
 /// A storage item key within its hashing algorithm
 pub enum StorageItemKey<T> {
+    Blake2_128(T),
+    Blake2_256(T),
     Blake2_128Concat(T),
+    Twox128(T),
+    Twox256(T),
     Twox64Concat(T),
+    Identity(T),
+}
+
+/// A storage item key that is already SCALE encoded, hence it is hashed as is
+pub struct EncodedKey<'a>(pub &'a [u8]);
+
+impl Encode for EncodedKey<'_> {
+    fn size_hint(&self) -> usize {
+        self.0.len()
+    }
+
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        dest.write(self.0)
+    }
+}
+
+impl<T> StorageItemKey<T> {
+    pub fn new(hasher: KeyHasher, key: T) -> Self {
+        match hasher {
+            KeyHasher::Blake2_128 => StorageItemKey::Blake2_128(key),
+            KeyHasher::Blake2_256 => StorageItemKey::Blake2_256(key),
+            KeyHasher::Blake2_128Concat => StorageItemKey::Blake2_128Concat(key),
+            KeyHasher::Twox128 => StorageItemKey::Twox128(key),
+            KeyHasher::Twox256 => StorageItemKey::Twox256(key),
+            KeyHasher::Twox64Concat => StorageItemKey::Twox64Concat(key),
+            KeyHasher::Identity => StorageItemKey::Identity(key),
+        }
+    }
 }
 
 impl BalanceStorageKeyBuilder {
@@ -30,15 +74,59 @@ impl BalanceStorageKeyBuilder {
         }
     }
 
+    /// Starts the key of a storage item, i.e. `twox128(pallet) ++ twox128(item)`
+    pub fn from_storage_item(pallet: &str, item: &str) -> Self {
+        let mut prefix = Vec::new();
+        prefix.extend_from_slice(&twox_128(pallet.as_bytes()));
+        prefix.extend_from_slice(&twox_128(item.as_bytes()));
+
+        Self::from_prefix(&prefix)
+    }
+
+    /// Builds the storage key that holds the balance of `account` for the asset
+    pub fn from_layout(
+        layout: &StorageKeyLayout,
+        asset_id: AssetId,
+        account: AccountId,
+    ) -> BalancesStorageKey {
+        match layout {
+            StorageKeyLayout::Prefix(prefix) => Self::from_prefix(prefix)
+                .push_item_key(StorageItemKey::Blake2_128Concat(account))
+                .build(),
+            StorageKeyLayout::Map { pallet, item, keys } => keys
+                .iter()
+                .fold(
+                    Self::from_storage_item(pallet, item),
+                    |builder, key| match key {
+                        StorageKeyComponent::AssetId(hasher) => {
+                            builder.push_item_key(StorageItemKey::new(*hasher, asset_id))
+                        }
+                        StorageKeyComponent::Account(hasher) => {
+                            builder.push_item_key(StorageItemKey::new(*hasher, account))
+                        }
+                        StorageKeyComponent::Encoded(hasher, key) => {
+                            builder.push_item_key(StorageItemKey::new(*hasher, EncodedKey(key)))
+                        }
+                    },
+                )
+                .build(),
+        }
+    }
+
     pub fn push_item_key<T: Encode>(self, key: StorageItemKey<T>) -> Self {
         let mut suffix = self.suffix;
         match key {
+            StorageItemKey::Blake2_128(key) => suffix.push(ContractBlake2_128::hash(&key.encode())),
+            StorageItemKey::Blake2_256(key) => suffix.push(ContractBlake2_256::hash(&key.encode())),
             StorageItemKey::Blake2_128Concat(key) => {
                 suffix.push(ContractBlake2_128Concat::hash(&key.encode()))
             }
+            StorageItemKey::Twox128(key) => suffix.push(ContractTwox128::hash(&key.encode())),
+            StorageItemKey::Twox256(key) => suffix.push(ContractTwox256::hash(&key.encode())),
             StorageItemKey::Twox64Concat(key) => {
                 suffix.push(ContractTwox64Concat::hash(&key.encode()))
             }
+            StorageItemKey::Identity(key) => suffix.push(ContractIdentity::hash(&key.encode())),
         };
 
         Self {
@@ -62,23 +150,50 @@ impl BalanceStorageKeyBuilder {
 #[derive(Default)]
 pub struct EmptyMessage;
 
+/// A request within its ABI encoding and its EIP-712 struct hash
+pub struct EncodedRequest {
+    pub encoded_msg: EncodedMessage,
+    pub struct_hash: H256,
+}
+
 #[derive(Default)]
 pub struct BalanceProverMessageBuilder<T>(T);
 
 impl BalanceProverMessageBuilder<EmptyMessage> {
-    pub fn request(self, request: ProverRequest) -> BalanceProverMessageBuilder<EncodedMessage> {
-        BalanceProverMessageBuilder(request.encode())
+    pub fn request<R: Encode + Eip712Struct>(
+        self,
+        request: R,
+    ) -> BalanceProverMessageBuilder<EncodedRequest> {
+        BalanceProverMessageBuilder(EncodedRequest {
+            encoded_msg: request.encode(),
+            struct_hash: request.struct_hash(),
+        })
     }
 }
 
-impl BalanceProverMessageBuilder<EncodedMessage> {
+impl BalanceProverMessageBuilder<EncodedRequest> {
     pub fn sign_request(
         self,
         pair: &ContractKeyPair,
+        scheme: &SignatureScheme,
+        recovery_id: RecoveryIdFormat,
+        target: &EvmTarget,
     ) -> BalanceProverMessageBuilder<SignedMessage> {
+        let msg_hash = ContractKeccak256::hash(&self.0.encoded_msg);
+        let digest = match scheme {
+            SignatureScheme::Keccak256 => msg_hash,
+            SignatureScheme::Eip712(domain) => domain.digest(target, &self.0.struct_hash),
+            SignatureScheme::PersonalSign => eth_signed_message_hash(msg_hash.as_bytes()),
+            SignatureScheme::IntendedValidator(validator) => {
+                intended_validator_hash(validator, msg_hash.as_bytes())
+            }
+        };
+
         BalanceProverMessageBuilder(SignedMessage {
-            signature: pair.sign(&self.0),
-            encoded_msg: self.0,
+            signature: recovery_id.apply(pair.sign_prehash(&digest)),
+            digest,
+            scheme: scheme.clone(),
+            encoded_msg: self.0.encoded_msg,
         })
     }
 }
@@ -88,3 +203,110 @@ impl BalanceProverMessageBuilder<SignedMessage> {
         self.0.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::balances::StorageKeyLayout;
+    use alloc::string::String;
+    use hex_literal::hex;
+    use sp_core::hashing::{blake2_128, twox_64};
+
+    const ALICE: [u8; 32] =
+        hex!("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d");
+
+    // `System::Account(alice)`
+    const ALICE_ACCOUNT_KEY: [u8; 80] = hex!(
+        "26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da9"
+        "de1e86a9a8c739864cf3cc5ec2bea59fd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+    );
+
+    #[test]
+    fn prefix_layout_appends_the_account() {
+        let prefix = hex!("26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da9");
+        let layout = StorageKeyLayout::Prefix(prefix.to_vec());
+
+        assert_eq!(
+            BalanceStorageKeyBuilder::from_layout(&layout, 0, AccountId::from(ALICE)),
+            ALICE_ACCOUNT_KEY
+        );
+    }
+
+    #[test]
+    fn map_layout_of_system_account() {
+        let layout = StorageKeyLayout::Map {
+            pallet: String::from("System"),
+            item: String::from("Account"),
+            keys: vec![StorageKeyComponent::Account(KeyHasher::Blake2_128Concat)],
+        };
+
+        assert_eq!(
+            BalanceStorageKeyBuilder::from_layout(&layout, 0, AccountId::from(ALICE)),
+            ALICE_ACCOUNT_KEY
+        );
+    }
+
+    #[test]
+    fn pallet_assets_layout_is_keyed_by_asset_then_account() {
+        let layout = StorageKeyLayout::pallet_assets("Assets".into(), "Account".into());
+        let asset_id: AssetId = 1984;
+
+        let expected = [
+            &hex!("682a59d51ab9e48a8c8cc418ff9708d2b99d880ec681799c0cf30e8886371da9")[..],
+            &blake2_128(&asset_id.encode()),
+            &asset_id.encode(),
+            &blake2_128(&ALICE),
+            &ALICE,
+        ]
+        .concat();
+        assert_eq!(
+            BalanceStorageKeyBuilder::from_layout(&layout, asset_id, AccountId::from(ALICE)),
+            expected
+        );
+    }
+
+    #[test]
+    fn orml_tokens_layout_is_keyed_by_account_then_currency_id() {
+        // `CurrencyId::Token(TokenSymbol::ACA)`, which is hashed as is
+        let currency_id = hex!("0000").to_vec();
+        let layout = StorageKeyLayout::orml_tokens(currency_id.clone());
+
+        let expected = [
+            &twox_128(b"Tokens")[..],
+            &twox_128(b"Accounts"),
+            &blake2_128(&ALICE),
+            &ALICE,
+            &twox_64(&currency_id),
+            &currency_id,
+        ]
+        .concat();
+        assert_eq!(
+            BalanceStorageKeyBuilder::from_layout(&layout, 0, AccountId::from(ALICE)),
+            expected
+        );
+    }
+
+    #[test]
+    fn identity_and_non_concat_hashers() {
+        let layout = StorageKeyLayout::Map {
+            pallet: String::from("Evm"),
+            item: String::from("AccountStorages"),
+            keys: vec![
+                StorageKeyComponent::AssetId(KeyHasher::Identity),
+                StorageKeyComponent::Account(KeyHasher::Twox128),
+            ],
+        };
+
+        let expected = [
+            &twox_128(b"Evm")[..],
+            &twox_128(b"AccountStorages"),
+            &7u32.encode(),
+            &twox_128(&ALICE),
+        ]
+        .concat();
+        assert_eq!(
+            BalanceStorageKeyBuilder::from_layout(&layout, 7, AccountId::from(ALICE)),
+            expected
+        );
+    }
+}
@@ -1,8 +1,42 @@
-use crate::types::{Error, Result};
+use crate::types::{rpc::RpcResponse, Error, Result};
 use alloc::format;
 use alloc::{string::String, vec, vec::Vec};
 use hex::FromHex;
 use pink_extension as pink;
+use serde::Deserialize;
+
+/// A random id for a JSON-RPC request
+pub fn request_id() -> u32 {
+    let mut id = [0u8; 4];
+    id.copy_from_slice(&pink::ext().getrandom(4));
+
+    u32::from_le_bytes(id)
+}
+
+/// Parses the JSON-RPC 2.0 response to the request with the given id, whose result must be present
+pub fn parse_result<'a, T: Deserialize<'a>>(resp_body: &'a [u8], id: u32) -> Result<T> {
+    parse_optional_result(resp_body, id)?.ok_or(Error::RpcInvalidBody)
+}
+
+/// Parses the JSON-RPC 2.0 response to the request with the given id, whose result may be `null`
+pub fn parse_optional_result<'a, T: Deserialize<'a>>(
+    resp_body: &'a [u8],
+    id: u32,
+) -> Result<Option<T>> {
+    let (response, _): (RpcResponse<T>, usize) =
+        serde_json_core::from_slice(resp_body).or(Err(Error::RpcInvalidBody))?;
+    if response.jsonrpc != "2.0" {
+        return Err(Error::RpcInvalidBody);
+    }
+
+    match (response.id, response.error) {
+        (Some(response_id), _) if response_id != id => Err(Error::RpcResponseIdMismatch),
+        // The id may be missing when the node could not parse the request
+        (_, Some(error)) => Err(error.into()),
+        (None, None) => Err(Error::RpcResponseIdMismatch),
+        (Some(_), None) => Ok(response.result),
+    }
+}
 
 pub fn call_rpc(rpc_node: &String, data: Vec<u8>) -> Result<Vec<u8>> {
     let content_length = format!("{}", data.len());
@@ -29,9 +63,77 @@ pub fn extract_hex_from(hex_string: &str) -> Result<Vec<u8>> {
     }
 
     let hex_string = &hex_string[2..];
-    Ok(Vec::from_hex(hex_string).map_err(|_| Error::InvalidHexData)?)
+    Vec::from_hex(hex_string).map_err(|_| Error::InvalidHexData)
+}
+
+pub fn extract_number_from(hex_string: &str) -> Result<u32> {
+    let hex_number = hex_string.strip_prefix("0x").ok_or(Error::InvalidHexData)?;
+
+    u32::from_str_radix(hex_number, 16).map_err(|_| Error::InvalidBlockNumber)
 }
 
 pub fn encode_to_hex(value: &[u8]) -> String {
     hex::encode(value)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::rpc::RpcErrorInfo;
+
+    #[test]
+    fn parses_the_result() {
+        let body = br#"{"jsonrpc":"2.0","result":"0x01","id":1}"#;
+
+        assert_eq!(parse_optional_result::<&str>(body, 1), Ok(Some("0x01")));
+        assert_eq!(parse_result::<&str>(body, 1), Ok("0x01"));
+    }
+
+    #[test]
+    fn parses_null_results() {
+        let body = br#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+
+        assert_eq!(parse_optional_result::<&str>(body, 1), Ok(None));
+        assert_eq!(parse_result::<&str>(body, 1), Err(Error::RpcInvalidBody));
+    }
+
+    #[test]
+    fn rejects_responses_to_other_requests() {
+        let body = br#"{"jsonrpc":"2.0","result":"0x01","id":2}"#;
+        assert_eq!(
+            parse_optional_result::<&str>(body, 1),
+            Err(Error::RpcResponseIdMismatch)
+        );
+
+        let body = br#"{"jsonrpc":"2.0","result":"0x01"}"#;
+        assert_eq!(
+            parse_optional_result::<&str>(body, 1),
+            Err(Error::RpcResponseIdMismatch)
+        );
+    }
+
+    #[test]
+    fn returns_the_error_of_responses_without_id() {
+        let body =
+            br#"{"jsonrpc":"2.0","error":{"code":-32700,"message":"Parse error"},"id":null}"#;
+
+        assert_eq!(
+            parse_optional_result::<&str>(body, 1),
+            Err(Error::RpcNodeError(RpcErrorInfo {
+                code: -32700,
+                message: String::from("Parse error"),
+                data: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn rejects_other_json_rpc_versions() {
+        let body = br#"{"jsonrpc":"1.0","result":"0x01","id":1}"#;
+
+        assert_eq!(
+            parse_optional_result::<&str>(body, 1),
+            Err(Error::RpcInvalidBody)
+        );
+    }
+}